@@ -1,3 +1,52 @@
 fn main() {
-    tauri_build::build()
+    // Listing the commands makes them subject to the ACL: only capabilities that grant
+    // `allow-<command>` can call them, which keeps them away from the remote provider pages
+    tauri_build::try_build(tauri_build::Attributes::new().app_manifest(
+        tauri_build::AppManifest::new().commands(&[
+            "send_to_all",
+            "get_send_queue",
+            "cancel_queued_prompt",
+            "reload_webview",
+            "reload_all",
+            "new_chat_all",
+            "regenerate_all",
+            "edit_last_prompt",
+            "list_models",
+            "select_model",
+            "get_current_models",
+            "get_last_responses",
+            "take_pending_input",
+            "stop_all",
+            "stop_provider",
+            "update_input_height",
+            "zoom_in",
+            "zoom_out",
+            "zoom_reset",
+            "clear_cache_all",
+            "clear_provider_data",
+            "refresh_gemini_session",
+            "focus_input",
+            "get_memory_usage",
+            "get_resource_usage",
+            "get_display_settings",
+            "set_display_settings",
+            "get_profiles",
+            "create_profile",
+            "rename_profile",
+            "delete_profile",
+            "switch_profile",
+            "list_sessions",
+            "open_session",
+            "rename_session",
+            "pin_session",
+            "delete_session",
+            "get_app_settings",
+            "set_app_settings",
+            "choose_inbox_folder",
+            "run_batch",
+            "cancel_batch",
+            "get_timing_stats",
+        ]),
+    ))
+    .expect("failed to run tauri-build")
 }
@@ -0,0 +1,371 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::{commands, responses, round, ProviderToastPayload};
+
+// Shown in the titlebar while a prompt is running
+const PREVIEW_CHARS: usize = 60;
+
+// Cancel flag of the running batch, if any
+static RUNNING: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
+
+#[derive(Debug)]
+struct BatchItem {
+    id: Option<String>,
+    prompt: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchProgressPayload {
+    running: bool,
+    completed: usize,
+    total: usize,
+    /// Start of the prompt being answered
+    current: Option<String>,
+    results_path: String,
+}
+
+/// Starts running every prompt in a CSV or JSONL file. Returns the path results are written to.
+pub fn run(app: &AppHandle, path: &Path) -> Result<PathBuf, String> {
+    let mut running = RUNNING.lock().unwrap();
+    if running.is_some() {
+        return Err("A batch is already running".to_string());
+    }
+
+    let items = read_items(path)?;
+    if items.is_empty() {
+        return Err("No prompts found".to_string());
+    }
+    let results_path = results_path(path);
+    let file = std::fs::File::create(&results_path).map_err(|e| e.to_string())?;
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    *running = Some(cancel.clone());
+
+    let app = app.clone();
+    let results = results_path.clone();
+    std::thread::spawn(move || {
+        let result = run_items(&app, &items, file, &results, cancel.clone());
+        *RUNNING.lock().unwrap() = None;
+        let message = match result {
+            Ok(completed) if cancel.load(Ordering::SeqCst) => {
+                format!(
+                    "Batch cancelled after {completed} of {} prompts",
+                    items.len()
+                )
+            }
+            Ok(completed) => format!(
+                "Batch finished: {completed} prompts, results in {}",
+                file_name(&results)
+            ),
+            Err(error) => {
+                eprintln!("Batch failed: {error}");
+                format!("Batch failed: {error}")
+            }
+        };
+        let payload = ProviderToastPayload {
+            provider: "seno".to_string(),
+            message,
+        };
+        let _ = app.emit_to("titlebar", "provider-toast", payload);
+    });
+    Ok(results_path)
+}
+
+/// Stops the running batch once the current prompt's wait ends. Returns false if none is running.
+pub fn cancel() -> bool {
+    match RUNNING.lock().unwrap().as_ref() {
+        Some(cancel) => {
+            cancel.store(true, Ordering::SeqCst);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Asks for a prompt file and runs it
+pub fn choose_and_run(app: &AppHandle) -> Result<(), String> {
+    let Some(file) = app
+        .dialog()
+        .file()
+        .add_filter("Prompts", &["csv", "jsonl", "ndjson"])
+        .blocking_pick_file()
+    else {
+        return Ok(());
+    };
+    let path = file.into_path().map_err(|e| e.to_string())?;
+    run(app, &path).map(|_| ())
+}
+
+fn results_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{stem}.results.jsonl"))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn read_items(path: &Path) -> Result<Vec<BatchItem>, String> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "csv" => read_csv(std::fs::File::open(path).map_err(|e| e.to_string())?),
+        "jsonl" | "ndjson" => {
+            read_jsonl(&std::fs::read_to_string(path).map_err(|e| e.to_string())?)
+        }
+        _ => Err("Unsupported batch file; use .csv or .jsonl".to_string()),
+    }
+}
+
+// Prompts come from a "prompt" column, or the first column when there is none
+fn read_csv(input: impl Read) -> Result<Vec<BatchItem>, String> {
+    let mut reader = csv::Reader::from_reader(input);
+    let headers = reader.headers().map_err(|e| e.to_string())?.clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.trim().eq_ignore_ascii_case(name))
+    };
+    let prompt_column = column("prompt").unwrap_or(0);
+    let id_column = column("id");
+
+    let mut items = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| format!("Row {}: {e}", index + 2))?;
+        let prompt = record.get(prompt_column).unwrap_or_default().trim();
+        if prompt.is_empty() {
+            continue;
+        }
+        items.push(BatchItem {
+            id: id_column
+                .and_then(|column| record.get(column))
+                .map(str::to_string),
+            prompt: prompt.to_string(),
+        });
+    }
+    Ok(items)
+}
+
+// Each line is a JSON string, or an object with "prompt" and an optional "id"
+fn read_jsonl(content: &str) -> Result<Vec<BatchItem>, String> {
+    let mut items = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value: Value =
+            serde_json::from_str(line).map_err(|e| format!("Line {}: {e}", index + 1))?;
+        let (id, prompt) = match &value {
+            Value::String(prompt) => (None, prompt.as_str()),
+            Value::Object(object) => {
+                let prompt = object
+                    .get("prompt")
+                    .and_then(Value::as_str)
+                    .ok_or(format!("Line {}: missing \"prompt\"", index + 1))?;
+                let id = object.get("id").map(|id| match id {
+                    Value::String(id) => id.clone(),
+                    id => id.to_string(),
+                });
+                (id, prompt)
+            }
+            _ => {
+                return Err(format!(
+                    "Line {}: expected a string or an object",
+                    index + 1
+                ))
+            }
+        };
+        if prompt.trim().is_empty() {
+            continue;
+        }
+        items.push(BatchItem {
+            id,
+            prompt: prompt.trim().to_string(),
+        });
+    }
+    Ok(items)
+}
+
+// Returns how many prompts were answered
+fn run_items(
+    app: &AppHandle,
+    items: &[BatchItem],
+    mut file: std::fs::File,
+    results_path: &Path,
+    cancel: Arc<AtomicBool>,
+) -> Result<usize, String> {
+    let progress = |completed: usize, current: Option<&str>| {
+        let payload = BatchProgressPayload {
+            running: current.is_some(),
+            completed,
+            total: items.len(),
+            current: current.map(|prompt| prompt.chars().take(PREVIEW_CHARS).collect()),
+            results_path: results_path.to_string_lossy().into_owned(),
+        };
+        let _ = app.emit_to("titlebar", "batch-progress", payload);
+    };
+
+    let mut completed = 0;
+    for (index, item) in items.iter().enumerate() {
+        if cancel.load(Ordering::SeqCst) {
+            break;
+        }
+        progress(completed, Some(&item.prompt));
+        let result = tauri::async_runtime::block_on(run_item(app, index, item, &cancel));
+        let line = serde_json::to_string(&result).map_err(|e| e.to_string())?;
+        writeln!(file, "{line}").map_err(|e| e.to_string())?;
+        if !cancel.load(Ordering::SeqCst) {
+            completed += 1;
+        }
+    }
+    progress(completed, None);
+    Ok(completed)
+}
+
+async fn run_item(
+    app: &AppHandle,
+    index: usize,
+    item: &BatchItem,
+    cancel: &Arc<AtomicBool>,
+) -> Value {
+    let started_at = round::now_millis();
+    let started = Instant::now();
+
+    // A fresh chat per prompt keeps earlier prompts out of the context
+    let new_chat = commands::new_chat_all(app.clone()).await;
+    let labels = crate::AI_SERVICES
+        .iter()
+        .map(|(label, _)| *label)
+        .collect::<Vec<_>>();
+    // How long each provider took, or None for ones that timed out
+    let mut answer_ms = BTreeMap::new();
+    let sent = match commands::send_prompt(app, &labels, &item.prompt, &[]) {
+        Ok(sent) => {
+            let waits = sent
+                .into_iter()
+                .map(|(result, delivery)| {
+                    let cancel = cancel.clone();
+                    let wait = tauri::async_runtime::spawn(async move {
+                        let sent = Instant::now();
+                        delivery
+                            .wait_for_answer(Some(cancel))
+                            .await
+                            .then(|| sent.elapsed().as_millis() as u64)
+                    });
+                    (result.provider, wait)
+                })
+                .collect::<Vec<_>>();
+            for (provider, wait) in waits {
+                answer_ms.insert(provider, wait.await.ok().flatten());
+            }
+            Ok(())
+        }
+        Err(error) => Err(error),
+    };
+    let responses = responses::last_all(app).await;
+    let cancelled = cancel.load(Ordering::SeqCst);
+
+    let providers = responses
+        .iter()
+        .map(|response| {
+            let answer_ms = answer_ms.get(response.provider.as_str()).copied().flatten();
+            json!({
+                "provider": response.provider,
+                "status": response.status,
+                "text": response.text,
+                "error": response.error,
+                "durationMs": answer_ms,
+                "timedOut": answer_ms.is_none() && !cancelled,
+            })
+        })
+        .collect::<Vec<_>>();
+    let error = match (new_chat, sent) {
+        (_, Err(error)) => Some(error),
+        (Err(error), _) => Some(format!("New chat failed: {error}")),
+        _ => None,
+    };
+    json!({
+        "index": index,
+        "id": item.id,
+        "prompt": item.prompt,
+        "startedAt": started_at,
+        "durationMs": started.elapsed().as_millis() as u64,
+        "cancelled": cancelled,
+        "error": error,
+        "responses": providers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompts(items: &[BatchItem]) -> Vec<&str> {
+        items.iter().map(|item| item.prompt.as_str()).collect()
+    }
+
+    #[test]
+    fn csv_reads_prompt_and_id_columns_by_header() {
+        let items = read_csv(
+            "ID,Notes, Prompt \nq1,skip,\"What is 2+2?\"\nq2,,\"Multi,\nline\"\n".as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(prompts(&items), ["What is 2+2?", "Multi,\nline"]);
+        assert_eq!(items[0].id.as_deref(), Some("q1"));
+        assert_eq!(items[1].id.as_deref(), Some("q2"));
+    }
+
+    #[test]
+    fn csv_without_prompt_header_uses_first_column() {
+        let items = read_csv("question,answer\nFirst,1\n  ,2\nSecond,3\n".as_bytes()).unwrap();
+        assert_eq!(prompts(&items), ["First", "Second"]);
+        assert!(items.iter().all(|item| item.id.is_none()));
+    }
+
+    #[test]
+    fn csv_reports_malformed_rows() {
+        let error = read_csv("prompt,id\nok,1\nbad,2,extra\n".as_bytes()).unwrap_err();
+        assert!(error.starts_with("Row 3:"), "{error}");
+    }
+
+    #[test]
+    fn jsonl_skips_blank_lines_and_prompts() {
+        let content =
+            "\n\"First\"\n   \n{\"prompt\": \" Second \", \"id\": 7}\n{\"prompt\": \"\"}\n\n";
+        let items = read_jsonl(content).unwrap();
+        assert_eq!(prompts(&items), ["First", "Second"]);
+        assert_eq!(items[0].id, None);
+        assert_eq!(items[1].id.as_deref(), Some("7"));
+    }
+
+    #[test]
+    fn jsonl_reports_the_failing_line() {
+        assert_eq!(
+            read_jsonl("\"ok\"\n\n{\"id\": 1}").unwrap_err(),
+            "Line 3: missing \"prompt\""
+        );
+        assert_eq!(
+            read_jsonl("42").unwrap_err(),
+            "Line 1: expected a string or an object"
+        );
+        assert!(read_jsonl("\"ok\"\nnot json")
+            .unwrap_err()
+            .starts_with("Line 2:"));
+    }
+}
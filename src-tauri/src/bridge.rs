@@ -0,0 +1,287 @@
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+// WebView2 and Chromium cut titles off at about 4096 characters, so replies go out in pieces
+const CHUNK_CHARS: usize = 2000;
+
+static NEXT_TOKEN: AtomicU64 = AtomicU64::new(1);
+
+struct PendingReply {
+    label: String,
+    /// Encoded chunks received so far, in order
+    chunks: Vec<String>,
+    tx: oneshot::Sender<String>,
+}
+
+// Request token -> reply being assembled
+type Pending = HashMap<u64, PendingReply>;
+
+static PENDING: Mutex<Option<Pending>> = Mutex::new(None);
+
+/// One piece of a reply title: `<token>:<index>:<count>:<chunk>`
+#[derive(Debug, PartialEq, Eq)]
+struct ReplyChunk<'a> {
+    token: u64,
+    index: usize,
+    count: usize,
+    chunk: &'a str,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Accepted {
+    /// Not a chunk this label is waiting for
+    Ignored,
+    /// Stored; the page should send the chunk at this index next
+    Next(usize),
+    /// Every chunk is in and the reply was handed to its waiter
+    Done,
+}
+
+/// Runs an async JS function body in a provider page and returns its JSON-decoded result.
+/// The page answers through its title, the same channel the status monitor uses, one chunk
+/// at a time: each chunk is acknowledged by asking the page for the next.
+pub async fn query<T: DeserializeOwned>(
+    webview: &tauri::Webview,
+    body: &str,
+    timeout: Duration,
+) -> Result<T, String> {
+    let label = webview.label().to_string();
+    let token = NEXT_TOKEN.fetch_add(1, Ordering::SeqCst);
+    let (tx, rx) = oneshot::channel();
+    PENDING
+        .lock()
+        .unwrap()
+        .get_or_insert_with(Pending::new)
+        .insert(
+            token,
+            PendingReply {
+                label,
+                chunks: Vec::new(),
+                tx,
+            },
+        );
+
+    let script = format!(
+        r#"
+    (async () => {{ {body} }})()
+        .catch(() => null)
+        .then((value) => {{
+            const payload = encodeURIComponent(JSON.stringify(value === undefined ? null : value));
+            const chunks = [];
+            for (let start = 0; start < payload.length; start += {CHUNK_CHARS}) {{
+                chunks.push(payload.slice(start, start + {CHUNK_CHARS}));
+            }}
+            window.__senoReplies = window.__senoReplies || {{}};
+            window.__senoReplies[{token}] = (index) => {{
+                const title = document.title || "";
+                const marker = title.lastIndexOf(" [seno:");
+                const base = marker === -1 ? title : title.slice(0, marker);
+                document.title = `${{base}} [seno:reply:{token}:${{index}}:${{chunks.length}}:${{chunks[index]}}]`;
+            }};
+            window.__senoReplies[{token}](0);
+        }});
+    "#
+    );
+    let reply = match webview.eval(script) {
+        Ok(()) => tokio::time::timeout(timeout, rx)
+            .await
+            .ok()
+            .and_then(Result::ok),
+        Err(error) => {
+            eprintln!("Failed to query {}: {error}", webview.label());
+            None
+        }
+    };
+
+    if let Some(pending) = PENDING.lock().unwrap().as_mut() {
+        pending.remove(&token);
+    }
+    let _ = webview.eval(format!("delete window.__senoReplies?.[{token}];"));
+
+    let reply = reply.ok_or_else(|| "Page did not respond".to_string())?;
+    serde_json::from_str(&reply).map_err(|e| e.to_string())
+}
+
+/// Called with the `<token>:<index>:<count>:<chunk>` part of a reply title
+pub fn resolve(webview: &tauri::Webview, reply: &str) {
+    let Some(chunk) = parse_reply(reply) else {
+        return;
+    };
+    let token = chunk.token;
+    let accepted = {
+        let mut guard = PENDING.lock().unwrap();
+        match guard.as_mut() {
+            Some(pending) => accept(pending, webview.label(), chunk),
+            None => Accepted::Ignored,
+        }
+    };
+    if let Accepted::Next(index) = accepted {
+        let _ = webview.eval(format!("window.__senoReplies?.[{token}]?.({index});"));
+    }
+}
+
+fn parse_reply(reply: &str) -> Option<ReplyChunk<'_>> {
+    let mut parts = reply.splitn(4, ':');
+    let token = parts.next()?.parse().ok()?;
+    let index = parts.next()?.parse().ok()?;
+    let count = parts.next()?.parse().ok()?;
+    let chunk = parts.next()?;
+    (index < count).then_some(ReplyChunk {
+        token,
+        index,
+        count,
+        chunk,
+    })
+}
+
+fn accept(pending: &mut Pending, label: &str, chunk: ReplyChunk) -> Accepted {
+    let Some(reply) = pending.get_mut(&chunk.token) else {
+        return Accepted::Ignored;
+    };
+    // A repeated or out-of-order title (e.g. from a reload) is dropped rather than spliced in
+    if reply.label != label || chunk.index != reply.chunks.len() {
+        return Accepted::Ignored;
+    }
+    reply.chunks.push(chunk.chunk.to_string());
+    if reply.chunks.len() < chunk.count {
+        return Accepted::Next(reply.chunks.len());
+    }
+
+    let Some(reply) = pending.remove(&chunk.token) else {
+        return Accepted::Ignored;
+    };
+    match decode(&reply.chunks.concat()) {
+        Some(payload) => {
+            let _ = reply.tx.send(payload);
+            Accepted::Done
+        }
+        None => Accepted::Ignored,
+    }
+}
+
+/// Reverses encodeURIComponent
+pub fn decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(index + 1..index + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(token: u64, label: &str) -> (Pending, oneshot::Receiver<String>) {
+        let (tx, rx) = oneshot::channel();
+        let reply = PendingReply {
+            label: label.to_string(),
+            chunks: Vec::new(),
+            tx,
+        };
+        (Pending::from([(token, reply)]), rx)
+    }
+
+    fn chunk(token: u64, index: usize, count: usize, chunk: &str) -> ReplyChunk<'_> {
+        ReplyChunk {
+            token,
+            index,
+            count,
+            chunk,
+        }
+    }
+
+    #[test]
+    fn decode_reverses_encode_uri_component() {
+        assert_eq!(decode("plain").as_deref(), Some("plain"));
+        assert_eq!(decode("a%20b%3A%5D%25").as_deref(), Some("a b:]%"));
+        assert_eq!(
+            decode("%E3%81%93%E3%82%93%F0%9F%91%8B").as_deref(),
+            Some("こん👋")
+        );
+    }
+
+    #[test]
+    fn decode_rejects_broken_escapes() {
+        assert_eq!(decode("%"), None);
+        assert_eq!(decode("%4"), None);
+        assert_eq!(decode("%zz"), None);
+        // A lone continuation byte isn't UTF-8
+        assert_eq!(decode("%80"), None);
+    }
+
+    #[test]
+    fn parse_reply_reads_token_index_count_and_chunk() {
+        assert_eq!(
+            parse_reply("7:1:3:%22a%3Ab"),
+            Some(chunk(7, 1, 3, "%22a%3Ab"))
+        );
+        assert_eq!(parse_reply("7:0:1:"), Some(chunk(7, 0, 1, "")));
+    }
+
+    #[test]
+    fn parse_reply_rejects_malformed_replies() {
+        assert_eq!(parse_reply("7:null"), None);
+        assert_eq!(parse_reply("x:0:1:null"), None);
+        assert_eq!(parse_reply("7:-1:1:null"), None);
+        assert_eq!(parse_reply("7:3:3:null"), None);
+    }
+
+    #[test]
+    fn accept_assembles_chunks_in_order() {
+        let (mut pending, mut rx) = pending(1, "claude");
+        assert_eq!(
+            accept(&mut pending, "claude", chunk(1, 0, 3, "%22he")),
+            Accepted::Next(1)
+        );
+        assert_eq!(
+            accept(&mut pending, "claude", chunk(1, 1, 3, "llo%2")),
+            Accepted::Next(2)
+        );
+        assert_eq!(
+            accept(&mut pending, "claude", chunk(1, 2, 3, "2")),
+            Accepted::Done
+        );
+        assert!(pending.is_empty());
+        assert_eq!(rx.try_recv().unwrap(), "\"hello\"");
+    }
+
+    #[test]
+    fn accept_ignores_other_labels_tokens_and_repeats() {
+        let (mut pending, mut rx) = pending(1, "claude");
+        assert_eq!(
+            accept(&mut pending, "gemini", chunk(1, 0, 2, "%22")),
+            Accepted::Ignored
+        );
+        assert_eq!(
+            accept(&mut pending, "claude", chunk(2, 0, 2, "%22")),
+            Accepted::Ignored
+        );
+        assert_eq!(
+            accept(&mut pending, "claude", chunk(1, 1, 2, "%22")),
+            Accepted::Ignored
+        );
+        assert_eq!(
+            accept(&mut pending, "claude", chunk(1, 0, 2, "%22")),
+            Accepted::Next(1)
+        );
+        assert_eq!(
+            accept(&mut pending, "claude", chunk(1, 0, 2, "%22")),
+            Accepted::Ignored
+        );
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Url};
+
+use crate::settings::{self, BudgetAction};
+use crate::{status, usage, ProviderStatusPayload, ProviderToastPayload};
+
+pub const RESUME_STATUS: &str = "resume";
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+// Give a reloaded provider time to settle before judging it again
+const COOLDOWN: Duration = Duration::from_secs(300);
+
+const SUSPENDED_PAGE: &str = r#"<!doctype html>
+<title>Suspended [seno:suspended]</title>
+<body style="margin:0;height:100vh;display:flex;align-items:center;justify-content:center;font:13px -apple-system,BlinkMacSystemFont,sans-serif;color:gray;cursor:pointer">
+Suspended to save memory. Click to restore.
+<script>
+const resume = () => { document.title = "Suspended [seno:resume]"; };
+window.addEventListener("pointerdown", resume);
+window.addEventListener("focus", resume);
+</script>
+</body>"#;
+
+// URL each suspended provider was on before being parked
+static SUSPENDED: Mutex<BTreeMap<String, Url>> = Mutex::new(BTreeMap::new());
+
+/// Starts the background thread that enforces the memory budget
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut last_action = HashMap::new();
+        loop {
+            std::thread::sleep(CHECK_INTERVAL);
+            if let Err(error) = check(&app, &mut last_action) {
+                eprintln!("Memory budget check failed: {error}");
+            }
+        }
+    });
+}
+
+fn check(app: &AppHandle, last_action: &mut HashMap<String, Instant>) -> Result<(), String> {
+    let budget = settings::current().memory_budget;
+    if !budget.enabled {
+        return Ok(());
+    }
+
+    let usage = usage::resource_usage(app)?;
+    // Without a per-provider breakdown only the total limit applies, taking idle providers in turn
+    let providers = if usage.breakdown_unavailable.is_some() {
+        crate::AI_SERVICES
+            .iter()
+            .map(|(label, _)| (label.to_string(), None))
+            .collect::<Vec<_>>()
+    } else {
+        usage
+            .providers
+            .iter()
+            .map(|p| (p.provider.clone(), Some(p.usage.memory_mb)))
+            .collect()
+    };
+    let mut candidates = providers
+        .iter()
+        .filter(|(provider, _)| status::is_idle(provider))
+        .filter(|(provider, _)| {
+            last_action
+                .get(provider)
+                .is_none_or(|at| at.elapsed() >= COOLDOWN)
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by(|(_, a), (_, b)| b.unwrap_or_default().total_cmp(&a.unwrap_or_default()));
+
+    let mut targets = match budget.provider_limit_mb {
+        Some(limit) => candidates
+            .iter()
+            .filter(|(_, memory_mb)| memory_mb.is_some_and(|memory_mb| memory_mb > limit as f64))
+            .map(|(provider, memory_mb)| {
+                (provider, format!("{:.0} MB", memory_mb.unwrap_or_default()))
+            })
+            .collect::<Vec<_>>(),
+        None => Vec::new(),
+    };
+    if targets.is_empty() {
+        if let Some(limit) = budget.total_limit_mb {
+            if usage.total.memory_mb > limit as f64 {
+                let total = format!("{:.0} MB in total", usage.total.memory_mb);
+                targets.extend(candidates.first().map(|(provider, _)| (provider, total)));
+            }
+        }
+    }
+
+    for (provider, memory) in targets {
+        apply(app, provider, budget.action, &memory)?;
+        last_action.insert(provider.clone(), Instant::now());
+    }
+
+    Ok(())
+}
+
+fn apply(app: &AppHandle, label: &str, action: BudgetAction, memory: &str) -> Result<(), String> {
+    let webview = app
+        .get_webview(label)
+        .ok_or_else(|| format!("Webview not found: {label}"))?;
+
+    let message = match action {
+        BudgetAction::Reload => {
+            webview
+                .eval("window.location.reload()")
+                .map_err(|e| e.to_string())?;
+            format!("Reloaded at {memory} (memory budget)")
+        }
+        BudgetAction::Suspend => {
+            let url = webview.url().map_err(|e| e.to_string())?;
+            let page = Url::parse(&format!(
+                "data:text/html;charset=utf-8,{}",
+                encode(SUSPENDED_PAGE)
+            ))
+            .map_err(|e| e.to_string())?;
+            SUSPENDED.lock().unwrap().insert(label.to_string(), url);
+            webview.navigate(page).map_err(|e| e.to_string())?;
+            format!("Suspended at {memory} (memory budget)")
+        }
+    };
+
+    eprintln!("Memory budget: {label}: {message}");
+    let payload = ProviderToastPayload {
+        provider: label.to_string(),
+        message,
+    };
+    app.emit_to("titlebar", "provider-toast", payload)
+        .map_err(|e| e.to_string())
+}
+
+/// Navigates a suspended provider back to where it was
+pub fn resume(webview: &tauri::Webview) -> Result<(), String> {
+    let Some(url) = SUSPENDED.lock().unwrap().remove(webview.label()) else {
+        return Ok(());
+    };
+    eprintln!("Memory budget: {}: restoring {url}", webview.label());
+    webview.navigate(url).map_err(|e| e.to_string())?;
+
+    let payload = ProviderStatusPayload {
+        provider: webview.label().to_string(),
+        status: "unknown".to_string(),
+    };
+    status::set(webview.label(), "unknown");
+    webview
+        .app_handle()
+        .emit_to("titlebar", "provider-status", payload)
+        .map_err(|e| e.to_string())
+}
+
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
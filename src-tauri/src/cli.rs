@@ -0,0 +1,172 @@
+use serde_json::Value;
+use std::io::Read;
+use std::time::{Duration, Instant};
+
+use crate::ipc::{self, Reply, Request};
+use crate::mcp;
+
+// How long to wait for a freshly launched app to start listening
+const LAUNCH_TIMEOUT: Duration = Duration::from_secs(30);
+const CONNECT_RETRY_INTERVAL: Duration = Duration::from_millis(250);
+
+const USAGE: &str = "Usage:
+  seno ask [--wait] [--json] <prompt>   Send a prompt to every provider (\"-\" reads stdin)
+  seno new                              Start a new chat on every provider
+  seno reload                           Reload every provider
+  seno status                           Show each provider's status
+  seno mcp                              Serve the Model Context Protocol on stdin/stdout";
+
+const COMMANDS: [&str; 8] = [
+    "ask", "new", "reload", "status", "mcp", "help", "--help", "-h",
+];
+
+/// Runs a CLI subcommand against the running app, launching it if needed.
+/// Returns None for any other launch, which starts the app (or hands its arguments to the running one).
+pub fn run(args: &[String]) -> Option<i32> {
+    let command = args.first()?.as_str();
+    if !COMMANDS.contains(&command) {
+        return None;
+    }
+    attach_console();
+    let rest = &args[1..];
+    let result = match command {
+        "ask" => ask(rest),
+        "new" => request(&Request::New).map(|_| ()),
+        "reload" => request(&Request::Reload).map(|_| ()),
+        "status" => status(),
+        "mcp" => mcp::serve(),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => return None,
+    };
+
+    Some(match result {
+        Ok(()) => 0,
+        Err(error) => {
+            eprintln!("seno: {error}");
+            1
+        }
+    })
+}
+
+// Release builds on Windows use the GUI subsystem and start without a console, so output
+// from a command typed in a terminal would go nowhere without attaching to the terminal's
+#[cfg(windows)]
+fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    // Fails harmlessly when started without a console, e.g. by an MCP client over pipes
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+fn ask(args: &[String]) -> Result<(), String> {
+    let mut wait = false;
+    let mut json = false;
+    let mut words = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--wait" | "-w" => wait = true,
+            "--json" => json = true,
+            _ => words.push(arg.as_str()),
+        }
+    }
+
+    let mut text = words.join(" ");
+    if text == "-" {
+        text.clear();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|e| e.to_string())?;
+    }
+    if text.trim().is_empty() {
+        return Err(format!("missing prompt\n{USAGE}"));
+    }
+
+    let data = request(&Request::Ask { text, wait })?;
+    if json {
+        println!("{data:#}");
+    } else if wait {
+        for response in data.as_array().into_iter().flatten() {
+            let provider = response["provider"].as_str().unwrap_or_default();
+            println!("## {}\n", crate::provider_name(provider));
+            match response["text"].as_str() {
+                Some(text) => println!("{text}\n"),
+                None => println!("(no answer captured)\n"),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn status() -> Result<(), String> {
+    let data = request(&Request::Status)?;
+    for provider in data.as_array().into_iter().flatten() {
+        let label = provider["provider"].as_str().unwrap_or_default();
+        let status = provider["status"].as_str().unwrap_or("unknown");
+        let queued = provider["queued"].as_u64().unwrap_or_default();
+        if queued > 0 {
+            println!(
+                "{}: {status} ({queued} queued)",
+                crate::provider_name(label)
+            );
+        } else {
+            println!("{}: {status}", crate::provider_name(label));
+        }
+    }
+    Ok(())
+}
+
+/// Sends a request to the running app, launching it first if needed
+pub(crate) fn request(request: &Request) -> Result<Value, String> {
+    let reply = match ipc::send(request) {
+        Ok(reply) => reply,
+        Err(_) => {
+            launch()?;
+            connect_with_retry(request)?
+        }
+    };
+    into_result(reply)
+}
+
+fn into_result(reply: Reply) -> Result<Value, String> {
+    if reply.ok {
+        Ok(reply.data)
+    } else {
+        Err(reply.error.unwrap_or_else(|| "request failed".to_string()))
+    }
+}
+
+// Starts the app in the background with no arguments
+fn launch() -> Result<(), String> {
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    std::process::Command::new(exe)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map_err(|e| format!("failed to launch Seno: {e}"))?;
+    Ok(())
+}
+
+fn connect_with_retry(request: &Request) -> Result<Reply, String> {
+    let started = Instant::now();
+    loop {
+        match ipc::send(request) {
+            Ok(reply) => return Ok(reply),
+            Err(error) if started.elapsed() >= LAUNCH_TIMEOUT => {
+                return Err(format!("Seno did not start: {error}"));
+            }
+            Err(_) => std::thread::sleep(CONNECT_RETRY_INTERVAL),
+        }
+    }
+}
@@ -1,17 +1,15 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Mutex;
-use tauri::Manager;
-use sysinfo::{Pid, System};
-use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
 
-#[cfg(target_os = "macos")]
-use std::collections::HashSet;
-#[cfg(target_os = "macos")]
-use std::path::Path;
-#[cfg(target_os = "macos")]
-use libproc::processes;
-
-use crate::{injector, layout, GEMINI_REINJECT_SCRIPT};
+use crate::{
+    batch, bridge, http_api, injector, launch, layout, models, profile, queue, ready, responses,
+    round, sessions, settings, status, timing, usage, GEMINI_REINJECT_SCRIPT,
+};
 
 const AI_SERVICES: [&str; 3] = ["claude", "chatgpt", "gemini"];
 
@@ -32,10 +30,22 @@ impl Default for DisplaySettings {
     fn default() -> Self {
         Self {
             elements: vec![
-                TitlebarElement { id: "memory".to_string(), visible: true },
-                TitlebarElement { id: "serviceStatus".to_string(), visible: true },
-                TitlebarElement { id: "geminiReinject".to_string(), visible: true },
-                TitlebarElement { id: "providerToast".to_string(), visible: true },
+                TitlebarElement {
+                    id: "memory".to_string(),
+                    visible: true,
+                },
+                TitlebarElement {
+                    id: "serviceStatus".to_string(),
+                    visible: true,
+                },
+                TitlebarElement {
+                    id: "geminiReinject".to_string(),
+                    visible: true,
+                },
+                TitlebarElement {
+                    id: "providerToast".to_string(),
+                    visible: true,
+                },
             ],
         }
     }
@@ -61,18 +71,103 @@ fn ensure_settings_initialized() -> DisplaySettings {
 // Default is 100%
 static ZOOM_LEVEL: AtomicU32 = AtomicU32::new(100);
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendResult {
+    pub provider: String,
+    /// Requested options this provider has no toggle for
+    pub unsupported: Vec<injector::SendOption>,
+    /// Waiting behind the answer in progress; delivery problems are shown as a toast later
+    pub queued: bool,
+    /// Why the prompt wasn't submitted
+    pub error: Option<String>,
+}
+
+#[tauri::command]
+pub async fn send_to_all(
+    app: tauri::AppHandle,
+    text: String,
+    options: Option<Vec<injector::SendOption>>,
+) -> Result<Vec<SendResult>, String> {
+    let sent = send_prompt(&app, &AI_SERVICES, &text, &options.unwrap_or_default())?;
+    let mut results = Vec::new();
+    for (mut result, delivery) in sent {
+        if !delivery.queued {
+            result.error = delivery.wait().await.err();
+        }
+        results.push(result);
+    }
+    Ok(results)
+}
+
+/// Sends a prompt to the given providers, returning each one's pending delivery
+pub fn send_prompt(
+    app: &tauri::AppHandle,
+    labels: &[&str],
+    text: &str,
+    options: &[injector::SendOption],
+) -> Result<Vec<(SendResult, queue::Delivery)>, String> {
+    let mut results = Vec::new();
+    // Providers still answering the previous prompt get this one once they go idle
+    for label in labels {
+        let (supported, unsupported): (Vec<_>, Vec<_>) = options
+            .iter()
+            .partition(|option| injector::send_option_label(label, **option).is_some());
+        let delivery = queue::push(app, label, text, &supported)?;
+        let result = SendResult {
+            provider: label.to_string(),
+            unsupported,
+            queued: delivery.queued,
+            error: None,
+        };
+        results.push((result, delivery));
+    }
+
+    round::start(labels, text);
+    if sessions::note_prompt(app, &profile::active_profile().id, text)? {
+        notify_sessions_changed(app)?;
+    }
+
+    Ok(results)
+}
+
+#[tauri::command]
+pub fn get_send_queue(provider: String) -> Vec<queue::QueuedPrompt> {
+    queue::list(&provider)
+}
+
+/// Drops a queued prompt before it is delivered; without an id the provider's whole queue is dropped
+#[tauri::command]
+pub fn cancel_queued_prompt(
+    app: tauri::AppHandle,
+    provider: String,
+    id: Option<u64>,
+) -> Result<(), String> {
+    queue::cancel(&app, &provider, id)
+}
+
+#[tauri::command]
+pub async fn reload_webview(app: tauri::AppHandle, label: String) -> Result<(), String> {
+    if let Some(webview) = app.get_webview(&label) {
+        webview
+            .eval("window.location.reload()")
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
 #[tauri::command]
-pub async fn send_to_all(app: tauri::AppHandle, text: String) -> Result<(), String> {
+pub async fn reload_all(app: tauri::AppHandle) -> Result<(), String> {
     let handles = AI_SERVICES
         .iter()
         .map(|label| {
             let app = app.clone();
             let label = label.to_string();
-            let text = text.clone();
             tauri::async_runtime::spawn(async move {
                 if let Some(webview) = app.get_webview(&label) {
-                    let script = injector::get_send_script(&label, &text);
-                    webview.eval(&script).map_err(|e| e.to_string())?;
+                    webview
+                        .eval("window.location.reload()")
+                        .map_err(|e| e.to_string())?;
                 }
                 Ok::<(), String>(())
             })
@@ -86,69 +181,386 @@ pub async fn send_to_all(app: tauri::AppHandle, text: String) -> Result<(), Stri
     Ok(())
 }
 
+/// Stops the response in progress on one provider. Returns false if it wasn't streaming.
 #[tauri::command]
-pub async fn reload_webview(app: tauri::AppHandle, label: String) -> Result<(), String> {
-    if let Some(webview) = app.get_webview(&label) {
-        webview
-            .eval("window.location.reload()")
-            .map_err(|e| e.to_string())?;
+pub async fn stop_provider(app: tauri::AppHandle, provider: String) -> Result<bool, String> {
+    if status::get(&provider).as_deref() != Some("streaming") {
+        return Ok(false);
     }
-    Ok(())
+    let webview = app
+        .get_webview(&provider)
+        .ok_or_else(|| format!("Webview not found: {provider}"))?;
+    webview
+        .eval(injector::get_stop_script(&provider))
+        .map_err(|e| e.to_string())?;
+    Ok(true)
 }
 
+/// Stops every streaming provider and returns the ones that were stopped
 #[tauri::command]
-pub async fn reload_all(app: tauri::AppHandle) -> Result<(), String> {
+pub async fn stop_all(app: tauri::AppHandle) -> Result<Vec<String>, String> {
+    let mut stopped = Vec::new();
+    for label in AI_SERVICES {
+        if stop_provider(app.clone(), label.to_string()).await? {
+            stopped.push(label.to_string());
+        }
+    }
+    Ok(stopped)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderActionResult {
+    pub provider: String,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+impl ProviderActionResult {
+    fn from_result(provider: &str, result: Result<(), String>) -> Self {
+        if let Err(error) = &result {
+            eprintln!("{} failed: {error}", crate::provider_name(provider));
+        }
+        Self {
+            provider: provider.to_string(),
+            ok: result.is_ok(),
+            error: result.err(),
+        }
+    }
+}
+
+// How long a provider gets to start streaming after a retry is clicked
+const RESTART_TIMEOUT: Duration = Duration::from_secs(5);
+const STATUS_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Asks every provider to regenerate its latest answer and reports which ones started streaming again
+#[tauri::command]
+pub async fn regenerate_all(app: tauri::AppHandle) -> Result<Vec<ProviderActionResult>, String> {
     let handles = AI_SERVICES
         .iter()
         .map(|label| {
             let app = app.clone();
             let label = label.to_string();
             tauri::async_runtime::spawn(async move {
-                if let Some(webview) = app.get_webview(&label) {
-                    webview
-                        .eval("window.location.reload()")
-                        .map_err(|e| e.to_string())?;
+                let result = match app.get_webview(&label) {
+                    Some(webview) => regenerate(&webview, &label).await,
+                    None => Err(format!("Webview not found: {label}")),
+                };
+                if let Err(error) = &result {
+                    let payload = crate::ProviderToastPayload {
+                        provider: label.clone(),
+                        message: format!("Regenerate failed: {error}"),
+                    };
+                    let _ = app.emit_to("titlebar", "provider-toast", payload);
                 }
-                Ok::<(), String>(())
+                ProviderActionResult::from_result(&label, result)
             })
         })
         .collect::<Vec<_>>();
 
+    let mut results = Vec::new();
     for handle in handles {
-        handle.await.map_err(|e| e.to_string())??;
+        results.push(handle.await.map_err(|e| e.to_string())?);
     }
+    Ok(results)
+}
 
-    Ok(())
+async fn regenerate(webview: &tauri::Webview, label: &str) -> Result<(), String> {
+    if status::get(label).as_deref() == Some("streaming") {
+        return Err("Still streaming".to_string());
+    }
+    webview
+        .eval(injector::get_regenerate_script(label))
+        .map_err(|e| e.to_string())?;
+    if wait_for_streaming(label, RESTART_TIMEOUT).await {
+        Ok(())
+    } else {
+        Err("Did not start streaming".to_string())
+    }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EditPromptMethod {
+    Edit,
+    Send,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditPromptResult {
+    pub provider: String,
+    pub ok: bool,
+    pub method: Option<EditPromptMethod>,
+    pub error: Option<String>,
+}
+
+// The edit script waits for up to three controls in turn, each for up to 5 seconds
+const EDIT_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Replaces the latest prompt in every provider's thread; providers that can't edit get a fresh send
 #[tauri::command]
-pub async fn new_chat_all(app: tauri::AppHandle) -> Result<(), String> {
-    let script = injector::get_new_chat_script();
+pub async fn edit_last_prompt(
+    app: tauri::AppHandle,
+    text: String,
+) -> Result<Vec<EditPromptResult>, String> {
+    // Edited or sent fresh, every provider now answers this prompt
+    round::start(&AI_SERVICES, &text);
     let handles = AI_SERVICES
         .iter()
         .map(|label| {
             let app = app.clone();
             let label = label.to_string();
+            let text = text.clone();
             tauri::async_runtime::spawn(async move {
-                if let Some(webview) = app.get_webview(&label) {
-                    webview.eval(script).map_err(|e| e.to_string())?;
+                let result = match app.get_webview(&label) {
+                    Some(webview) => edit_or_send(&app, &webview, &label, &text).await,
+                    None => Err(format!("Webview not found: {label}")),
+                };
+                match result {
+                    Ok(method) => EditPromptResult {
+                        provider: label,
+                        ok: true,
+                        method: Some(method),
+                        error: None,
+                    },
+                    Err(error) => {
+                        eprintln!("Edit failed for {label}: {error}");
+                        EditPromptResult {
+                            provider: label,
+                            ok: false,
+                            method: None,
+                            error: Some(error),
+                        }
+                    }
                 }
-                Ok::<(), String>(())
             })
         })
         .collect::<Vec<_>>();
 
+    let mut results = Vec::new();
     for handle in handles {
-        handle.await.map_err(|e| e.to_string())??;
+        results.push(handle.await.map_err(|e| e.to_string())?);
+    }
+    Ok(results)
+}
+
+async fn edit_or_send(
+    app: &tauri::AppHandle,
+    webview: &tauri::Webview,
+    label: &str,
+    text: &str,
+) -> Result<EditPromptMethod, String> {
+    // The edit controls stay hidden until the current answer stops
+    if status::get(label).as_deref() == Some("streaming") {
+        webview
+            .eval(injector::get_stop_script(label))
+            .map_err(|e| e.to_string())?;
+        if !wait_for_stopped(label, RESTART_TIMEOUT).await {
+            return Err("Did not stop streaming".to_string());
+        }
+    }
+
+    let script = injector::get_edit_last_prompt_script(label, text);
+    let outcome = bridge::query::<String>(webview, &script, EDIT_TIMEOUT).await?;
+    match outcome.as_str() {
+        injector::EDIT_SUBMITTED => {
+            timing::on_sent(label);
+            Ok(EditPromptMethod::Edit)
+        }
+        injector::EDIT_UNSUPPORTED | injector::EDIT_NO_MESSAGE => {
+            eprintln!("Editing not available for {label}, sending as a new prompt");
+            let delivery = queue::push(app, label, text, &[])?;
+            if !delivery.queued {
+                delivery.wait().await?;
+            }
+            Ok(EditPromptMethod::Send)
+        }
+        _ => Err("Could not submit the edited prompt".to_string()),
     }
+}
 
-    // Delay to let SPA navigation complete before restoring focus
+async fn wait_for_stopped(label: &str, timeout: Duration) -> bool {
+    let started = Instant::now();
+    while started.elapsed() < timeout {
+        tokio::time::sleep(STATUS_POLL_INTERVAL).await;
+        if status::get(label).as_deref() != Some("streaming") {
+            return true;
+        }
+    }
+    false
+}
+
+/// Waits for the status monitor to report that the provider is streaming
+async fn wait_for_streaming(label: &str, timeout: Duration) -> bool {
+    let started = Instant::now();
+    while started.elapsed() < timeout {
+        tokio::time::sleep(STATUS_POLL_INTERVAL).await;
+        if status::get(label).as_deref() == Some("streaming") {
+            return true;
+        }
+    }
+    false
+}
+
+/// Latest answer text from each provider, read from the page
+#[tauri::command]
+pub async fn get_last_responses(app: tauri::AppHandle) -> Vec<responses::ProviderResponse> {
+    responses::last_all(&app).await
+}
+
+#[tauri::command]
+pub async fn list_models(app: tauri::AppHandle, provider: String) -> Result<Vec<String>, String> {
+    models::list(&app, &provider).await
+}
+
+/// Selects a model by name; a partial name such as "sonnet" or "flash" is enough
+#[tauri::command]
+pub async fn select_model(
+    app: tauri::AppHandle,
+    provider: String,
+    model: String,
+) -> Result<String, String> {
+    models::select(&app, &provider, &model).await
+}
+
+/// Provider label -> model currently shown in its model selector
+#[tauri::command]
+pub fn get_current_models() -> BTreeMap<String, String> {
+    models::current()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NewChatMethod {
+    Button,
+    Navigate,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewChatResult {
+    pub provider: String,
+    pub ok: bool,
+    pub method: Option<NewChatMethod>,
+    pub error: Option<String>,
+}
+
+// How long to wait for a fresh thread to show up in the URL
+const NEW_CHAT_TIMEOUT: Duration = Duration::from_millis(2500);
+const NEW_CHAT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+// Checking the page for an empty thread is one bridge round trip
+const EMPTY_THREAD_TIMEOUT: Duration = Duration::from_millis(1000);
+
+#[tauri::command]
+pub async fn new_chat_all(app: tauri::AppHandle) -> Result<Vec<NewChatResult>, String> {
+    let handles = AI_SERVICES
+        .iter()
+        .map(|label| {
+            let app = app.clone();
+            let label = label.to_string();
+            tauri::async_runtime::spawn(async move {
+                let result = match app.get_webview(&label) {
+                    Some(webview) => open_new_chat(&webview, &label).await,
+                    None => Err(format!("Webview not found: {label}")),
+                };
+                match result {
+                    Ok(method) => NewChatResult {
+                        provider: label,
+                        ok: true,
+                        method: Some(method),
+                        error: None,
+                    },
+                    Err(error) => {
+                        eprintln!("New chat failed for {label}: {error}");
+                        NewChatResult {
+                            provider: label,
+                            ok: false,
+                            method: None,
+                            error: Some(error),
+                        }
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut results = Vec::new();
+    for handle in handles {
+        results.push(handle.await.map_err(|e| e.to_string())?);
+    }
+
+    sessions::start(&app, &profile::active_profile().id)?;
+    notify_sessions_changed(&app)?;
+
+    // Restore focus once every provider's editor has rendered, so none of them steals it back
     tauri::async_runtime::spawn(async move {
-        std::thread::sleep(std::time::Duration::from_millis(300));
+        let waits = AI_SERVICES
+            .iter()
+            .filter_map(|label| app.get_webview(label))
+            .map(|webview| {
+                tauri::async_runtime::spawn(async move {
+                    ready::wait(&webview, ready::EDITOR_TIMEOUT).await
+                })
+            })
+            .collect::<Vec<_>>();
+        for wait in waits {
+            let _ = wait.await;
+        }
         let _ = focus_input(app).await;
     });
 
-    Ok(())
+    Ok(results)
+}
+
+/// Clicks the provider's new-chat control, falling back to its new-chat URL
+async fn open_new_chat(webview: &tauri::Webview, label: &str) -> Result<NewChatMethod, String> {
+    let before = webview.url().ok();
+    let script = injector::get_new_chat_script(label);
+    webview.eval(&script).map_err(|e| e.to_string())?;
+    if wait_for_new_chat(webview, label, before.as_ref()).await {
+        return Ok(NewChatMethod::Button);
+    }
+
+    let url =
+        injector::get_new_chat_url(label).ok_or_else(|| format!("Unknown provider: {label}"))?;
+    let url = tauri::Url::parse(url).map_err(|e| e.to_string())?;
+    webview.navigate(url).map_err(|e| e.to_string())?;
+    if wait_for_new_chat(webview, label, before.as_ref()).await {
+        return Ok(NewChatMethod::Navigate);
+    }
+
+    Err("No fresh thread opened".to_string())
+}
+
+async fn wait_for_new_chat(
+    webview: &tauri::Webview,
+    label: &str,
+    before: Option<&tauri::Url>,
+) -> bool {
+    let started = Instant::now();
+    while started.elapsed() < NEW_CHAT_TIMEOUT {
+        tokio::time::sleep(NEW_CHAT_POLL_INTERVAL).await;
+        let Ok(url) = webview.url() else {
+            continue;
+        };
+        if !injector::is_new_chat_url(label, &url) {
+            continue;
+        }
+        // A page already on its new-chat URL may still hold a draft or an unsaved thread,
+        // so an unchanged URL only counts once the page shows an empty thread
+        if before != Some(&url) || is_thread_empty(webview, label).await {
+            return true;
+        }
+    }
+    false
+}
+
+async fn is_thread_empty(webview: &tauri::Webview, label: &str) -> bool {
+    let script = injector::get_empty_thread_script(label);
+    bridge::query::<bool>(webview, &script, EMPTY_THREAD_TIMEOUT)
+        .await
+        .unwrap_or(false)
 }
 
 #[tauri::command]
@@ -163,11 +575,17 @@ pub async fn update_input_height(app: tauri::AppHandle, height: f64) -> Result<(
     layout::set_input_bar_height(height);
 
     let app_handle = app.clone();
-    let labels = AI_SERVICES.iter().map(|label| label.to_string()).collect::<Vec<_>>();
+    let labels = AI_SERVICES
+        .iter()
+        .map(|label| label.to_string())
+        .collect::<Vec<_>>();
 
     main_window
         .run_on_main_thread(move || {
-            let label_refs = labels.iter().map(|label| label.as_str()).collect::<Vec<_>>();
+            let label_refs = labels
+                .iter()
+                .map(|label| label.as_str())
+                .collect::<Vec<_>>();
             if let Err(error) =
                 layout::apply_layout(&app_handle, &label_refs, physical_size, scale_factor)
             {
@@ -272,6 +690,96 @@ pub async fn clear_cache_all(app: tauri::AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DataScope {
+    Cookies,
+    Storage,
+    IndexedDb,
+    ServiceWorkers,
+    DataStore,
+}
+
+pub const ALL_DATA_SCOPES: [DataScope; 5] = [
+    DataScope::Cookies,
+    DataScope::Storage,
+    DataScope::IndexedDb,
+    DataScope::ServiceWorkers,
+    DataScope::DataStore,
+];
+
+/// Wipes the selected data for one provider after confirmation. Returns false if cancelled.
+#[tauri::command]
+pub async fn clear_provider_data(
+    app: tauri::AppHandle,
+    label: String,
+    scopes: Vec<DataScope>,
+) -> Result<bool, String> {
+    if !AI_SERVICES.contains(&label.as_str()) {
+        return Err(format!("Unknown provider: {label}"));
+    }
+    if scopes.is_empty() {
+        return Ok(false);
+    }
+    let webview = app
+        .get_webview(&label)
+        .ok_or_else(|| format!("Webview not found: {label}"))?;
+
+    let name = crate::provider_name(&label);
+    let message = if scopes.contains(&DataScope::DataStore) {
+        format!("Clear all stored data for {name}? You will be signed out.")
+    } else {
+        let names = scopes
+            .iter()
+            .map(|scope| match scope {
+                DataScope::Cookies => "cookies",
+                DataScope::Storage => "local storage and caches",
+                DataScope::IndexedDb => "IndexedDB",
+                DataScope::ServiceWorkers => "service workers",
+                DataScope::DataStore => "all data",
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("Clear {names} for {name}? You may need to sign in again.")
+    };
+    let confirmed = app
+        .dialog()
+        .message(message)
+        .title("Clear Provider Data")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Clear".to_string(),
+            "Cancel".to_string(),
+        ))
+        .blocking_show();
+    if !confirmed {
+        return Ok(false);
+    }
+
+    if scopes.contains(&DataScope::Cookies) {
+        let cookies = webview.cookies().map_err(|e| e.to_string())?;
+        for cookie in cookies {
+            webview.delete_cookie(cookie).map_err(|e| e.to_string())?;
+        }
+    }
+
+    if scopes.contains(&DataScope::DataStore) {
+        webview
+            .clear_all_browsing_data()
+            .map_err(|e| e.to_string())?;
+    }
+
+    // Page-level data is cleared from inside the origin; the script reloads when done
+    let script = injector::get_clear_data_script(
+        scopes.contains(&DataScope::Storage),
+        scopes.contains(&DataScope::IndexedDb),
+        scopes.contains(&DataScope::ServiceWorkers),
+    );
+    webview.eval(&script).map_err(|e| e.to_string())?;
+
+    Ok(true)
+}
+
 #[tauri::command]
 pub async fn refresh_gemini_session(app: tauri::AppHandle) -> Result<(), String> {
     if let Some(webview) = app.get_webview("gemini") {
@@ -282,6 +790,12 @@ pub async fn refresh_gemini_session(app: tauri::AppHandle) -> Result<(), String>
     Ok(())
 }
 
+/// Text handed over by a launch (prompt argument or prompt file), if any
+#[tauri::command]
+pub fn take_pending_input() -> Option<String> {
+    launch::take_pending_input()
+}
+
 #[tauri::command]
 pub async fn focus_input(app: tauri::AppHandle) -> Result<(), String> {
     if let Some(webview) = app.get_webview("main") {
@@ -296,7 +810,10 @@ pub fn get_display_settings() -> DisplaySettings {
 }
 
 #[tauri::command]
-pub async fn set_display_settings(app: tauri::AppHandle, settings: DisplaySettings) -> Result<(), String> {
+pub async fn set_display_settings(
+    app: tauri::AppHandle,
+    settings: DisplaySettings,
+) -> Result<(), String> {
     {
         let mut current = DISPLAY_SETTINGS.lock().unwrap();
         *current = settings.clone();
@@ -347,111 +864,142 @@ pub async fn set_display_settings(app: tauri::AppHandle, settings: DisplaySettin
 }
 
 #[tauri::command]
-pub fn get_memory_usage() -> Result<f64, String> {
-    let pid = Pid::from_u32(std::process::id());
-    let mut system = System::new_all();
-    system.refresh_processes();
+pub fn get_app_settings() -> settings::AppSettings {
+    settings::current()
+}
 
-    let process = system
-        .process(pid)
-        .ok_or_else(|| "Process not found".to_string())?;
+#[tauri::command]
+pub fn set_app_settings(
+    app: tauri::AppHandle,
+    settings: settings::AppSettings,
+) -> Result<(), String> {
+    settings::set(&app, settings)?;
+    http_api::apply(&app);
+    Ok(())
+}
 
-    let mut total_memory = process.memory();
+/// Latest answer timings and rolling statistics for every provider
+#[tauri::command]
+pub fn get_timing_stats() -> Vec<timing::TimingStats> {
+    timing::all()
+}
 
-    #[cfg(target_os = "macos")]
-    {
-        let user_id = process.user_id().cloned();
-        let related_pids = macos_related_pids(&system, pid, process.start_time(), user_id);
-        total_memory += sum_memory_for_pids(&system, related_pids);
-    }
+/// Runs every prompt in a CSV or JSONL file in the background. Returns the results file path.
+#[tauri::command]
+pub fn run_batch(app: tauri::AppHandle, path: String) -> Result<String, String> {
+    let results = batch::run(&app, std::path::Path::new(&path))?;
+    Ok(results.to_string_lossy().into_owned())
+}
 
-    #[cfg(not(target_os = "macos"))]
-    {
-        let descendant_pids = system
-            .processes()
-            .values()
-            .filter(|proc| is_descendant(proc, pid, system.processes()))
-            .map(|proc| proc.pid());
-        total_memory += sum_memory_for_pids(&system, descendant_pids);
-    }
-
-    let memory_mb = total_memory as f64 / (1024.0 * 1024.0);
-    Ok(memory_mb)
-}
-
-#[cfg(target_os = "macos")]
-fn macos_webkit_data_store_path() -> Option<std::path::PathBuf> {
-    let home = std::env::var("HOME").ok()?;
-    let bundle_id = "com.seno.viewer";
-    let path = Path::new(&home).join("Library").join("WebKit").join(bundle_id);
-    path.exists().then_some(path)
-}
-
-#[cfg(target_os = "macos")]
-fn macos_related_pids(
-    system: &System,
-    root_pid: Pid,
-    start_time: u64,
-    user_id: Option<sysinfo::Uid>,
-) -> HashSet<Pid> {
-    let mut related_pids = HashSet::new();
-
-    for proc in system.processes().values() {
-        if is_descendant(proc, root_pid, system.processes()) {
-            related_pids.insert(proc.pid());
-        }
-        if proc.name().starts_with("seno ") {
-            related_pids.insert(proc.pid());
-        }
-    }
+#[tauri::command]
+pub fn cancel_batch() -> bool {
+    batch::cancel()
+}
 
-    if let Some(data_store_path) = macos_webkit_data_store_path() {
-        if let Ok(pids) = processes::pids_by_path(&data_store_path, false, false) {
-            for web_pid in pids {
-                related_pids.insert(Pid::from_u32(web_pid));
-            }
-        }
+// Async so the blocking dialog stays off the main thread
+#[tauri::command]
+pub async fn choose_inbox_folder(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    let Some(folder) = app.dialog().file().blocking_pick_folder() else {
+        return Ok(None);
+    };
+    let path = folder.into_path().map_err(|e| e.to_string())?;
+    Ok(Some(path.to_string_lossy().into_owned()))
+}
+
+#[tauri::command]
+pub fn get_profiles() -> profile::ProfileSettings {
+    profile::current()
+}
+
+#[tauri::command]
+pub fn create_profile(app: tauri::AppHandle, name: String) -> Result<profile::Profile, String> {
+    let created = profile::create(&app, &name)?;
+    notify_profiles_changed(&app)?;
+    Ok(created)
+}
+
+#[tauri::command]
+pub fn rename_profile(app: tauri::AppHandle, id: String, name: String) -> Result<(), String> {
+    profile::rename(&app, &id, &name)?;
+    notify_profiles_changed(&app)
+}
+
+#[tauri::command]
+pub async fn delete_profile(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    // Only inactive profiles can be deleted, so none of its webviews are open
+    let profile = profile::delete(&app, &id)?;
+    notify_profiles_changed(&app)?;
+    profile::remove_data(&app, &profile).await
+}
+
+#[tauri::command]
+pub async fn switch_profile(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    if profile::set_active(&app, &id)? {
+        crate::rebuild_ai_webviews(&app)?;
+        apply_zoom(&app, ZOOM_LEVEL.load(Ordering::SeqCst)).await?;
+        notify_sessions_changed(&app)?;
     }
 
-    for proc in system.processes().values() {
-        if proc.name() != "com.apple.WebKit.WebContent" {
-            continue;
-        }
-        if proc.start_time() < start_time {
+    // Always refresh so the menu check state matches the active profile
+    notify_profiles_changed(&app)
+}
+
+fn notify_profiles_changed(app: &tauri::AppHandle) -> Result<(), String> {
+    crate::refresh_profile_menu(app).map_err(|e| e.to_string())?;
+    app.emit("profiles-changed", profile::current())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_sessions() -> Vec<sessions::Session> {
+    sessions::list(&profile::active_profile().id)
+}
+
+#[tauri::command]
+pub async fn open_session(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    let urls = sessions::open(&app, &profile::active_profile().id, &id)?;
+    for label in AI_SERVICES.iter() {
+        let Some(url) = urls.get(*label) else {
             continue;
+        };
+        if let Some(webview) = app.get_webview(label) {
+            let url = tauri::Url::parse(url).map_err(|e| e.to_string())?;
+            webview.navigate(url).map_err(|e| e.to_string())?;
         }
-        if let Some(user_id) = user_id.as_ref() {
-            if proc.user_id() != Some(user_id) {
-                continue;
-            }
-        }
-        related_pids.insert(proc.pid());
     }
+    notify_sessions_changed(&app)
+}
 
-    related_pids
+#[tauri::command]
+pub fn rename_session(app: tauri::AppHandle, id: String, name: String) -> Result<(), String> {
+    sessions::rename(&app, &id, &name)?;
+    notify_sessions_changed(&app)
 }
 
-fn sum_memory_for_pids(
-    system: &System,
-    pids: impl IntoIterator<Item = Pid>,
-) -> u64 {
-    pids
-        .into_iter()
-        .filter_map(|pid| system.process(pid).map(|proc| proc.memory()))
-        .sum()
+#[tauri::command]
+pub fn pin_session(app: tauri::AppHandle, id: String, pinned: bool) -> Result<(), String> {
+    sessions::set_pinned(&app, &id, pinned)?;
+    notify_sessions_changed(&app)
 }
 
-fn is_descendant(
-    process: &sysinfo::Process,
-    root_pid: Pid,
-    processes: &std::collections::HashMap<Pid, sysinfo::Process>,
-) -> bool {
-    let mut current = process.parent();
-    while let Some(pid) = current {
-        if pid == root_pid {
-            return true;
-        }
-        current = processes.get(&pid).and_then(|proc| proc.parent());
-    }
-    false
+#[tauri::command]
+pub fn delete_session(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    sessions::delete(&app, &id)?;
+    notify_sessions_changed(&app)
+}
+
+fn notify_sessions_changed(app: &tauri::AppHandle) -> Result<(), String> {
+    crate::refresh_session_menu(app).map_err(|e| e.to_string())?;
+    app.emit("sessions-changed", list_sessions())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_memory_usage() -> Result<f64, String> {
+    usage::memory_usage()
+}
+
+#[tauri::command]
+pub fn get_resource_usage(app: tauri::AppHandle) -> Result<usage::ResourceUsage, String> {
+    usage::resource_usage(&app)
 }
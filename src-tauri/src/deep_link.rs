@@ -0,0 +1,194 @@
+use tauri::{AppHandle, Url};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::commands;
+
+pub const SCHEME: &str = "seno";
+
+// Links are shared in wikis, so keep prompts to a sane size
+const MAX_PROMPT_CHARS: usize = 20_000;
+// Shown in the confirmation dialog
+const PREVIEW_CHARS: usize = 300;
+
+#[derive(Debug)]
+enum Link {
+    /// seno://ask?text=...&providers=claude,gemini
+    Ask {
+        text: String,
+        providers: Vec<String>,
+    },
+    /// seno://session/<id>
+    Session { id: String },
+}
+
+fn parse(url: &Url) -> Result<Link, String> {
+    if url.scheme() != SCHEME {
+        return Err(format!("Not a {SCHEME}:// link"));
+    }
+
+    match url.host_str() {
+        Some("ask") => {
+            let mut text = None;
+            let mut providers = None;
+            for (key, value) in url.query_pairs() {
+                match key.as_ref() {
+                    "text" => text = Some(value.into_owned()),
+                    "providers" => providers = Some(parse_providers(&value)?),
+                    _ => {}
+                }
+            }
+            let text = text
+                .filter(|text| !text.trim().is_empty())
+                .ok_or("Missing text")?;
+            if text.chars().count() > MAX_PROMPT_CHARS {
+                return Err(format!(
+                    "Prompt is longer than {MAX_PROMPT_CHARS} characters"
+                ));
+            }
+            let providers = providers.unwrap_or_else(|| {
+                crate::AI_SERVICES
+                    .iter()
+                    .map(|(label, _)| label.to_string())
+                    .collect()
+            });
+            Ok(Link::Ask { text, providers })
+        }
+        Some("session") => {
+            let id = url.path().trim_matches('/');
+            let valid = !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+            if !valid {
+                return Err("Invalid session id".to_string());
+            }
+            Ok(Link::Session { id: id.to_string() })
+        }
+        _ => Err(format!("Unsupported link: {url}")),
+    }
+}
+
+fn parse_providers(value: &str) -> Result<Vec<String>, String> {
+    let mut providers = Vec::new();
+    for provider in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        if !crate::AI_SERVICES
+            .iter()
+            .any(|(label, _)| *label == provider)
+        {
+            return Err(format!("Unknown provider: {provider}"));
+        }
+        if !providers.iter().any(|p| p == provider) {
+            providers.push(provider.to_string());
+        }
+    }
+    if providers.is_empty() {
+        return Err("No providers given".to_string());
+    }
+    Ok(providers)
+}
+
+/// Opens a seno:// link. Prompts are sent only after the user confirms.
+pub async fn handle(app: &AppHandle, url: &str) -> Result<(), String> {
+    let url = Url::parse(url).map_err(|e| e.to_string())?;
+    match parse(&url)? {
+        Link::Ask { text, providers } => {
+            if !confirm_send(app, &text, &providers) {
+                return Ok(());
+            }
+            let labels = providers.iter().map(String::as_str).collect::<Vec<_>>();
+            commands::send_prompt(app, &labels, &text, &[]).map(|_| ())
+        }
+        Link::Session { id } => commands::open_session(app.clone(), id).await,
+    }
+}
+
+fn confirm_send(app: &AppHandle, text: &str, providers: &[String]) -> bool {
+    let mut preview = text.chars().take(PREVIEW_CHARS).collect::<String>();
+    if text.chars().count() > PREVIEW_CHARS {
+        preview.push('…');
+    }
+    let names = providers
+        .iter()
+        .map(|label| crate::provider_name(label))
+        .collect::<Vec<_>>()
+        .join(", ");
+    app.dialog()
+        .message(format!(
+            "A link wants to send this prompt to {names}:\n\n{preview}"
+        ))
+        .title("Send Prompt?")
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Send".to_string(),
+            "Cancel".to_string(),
+        ))
+        .blocking_show()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(url: &str) -> Result<Link, String> {
+        parse(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn ask_decodes_percent_encoded_text() {
+        let Ok(Link::Ask { text, providers }) =
+            parse_str("seno://ask?text=Hello%2C%20world%20%26%20more+words")
+        else {
+            panic!("expected an ask link");
+        };
+        assert_eq!(text, "Hello, world & more words");
+        assert_eq!(providers, ["claude", "chatgpt", "gemini"]);
+    }
+
+    #[test]
+    fn ask_keeps_known_providers_once_in_order() {
+        let Ok(Link::Ask { providers, .. }) =
+            parse_str("seno://ask?text=hi&providers=gemini,%20claude,gemini")
+        else {
+            panic!("expected an ask link");
+        };
+        assert_eq!(providers, ["gemini", "claude"]);
+    }
+
+    #[test]
+    fn ask_rejects_unknown_or_missing_providers() {
+        assert_eq!(
+            parse_str("seno://ask?text=hi&providers=claude,copilot").unwrap_err(),
+            "Unknown provider: copilot"
+        );
+        assert_eq!(
+            parse_str("seno://ask?text=hi&providers=,").unwrap_err(),
+            "No providers given"
+        );
+    }
+
+    #[test]
+    fn ask_requires_text() {
+        assert!(parse_str("seno://ask").is_err());
+        assert!(parse_str("seno://ask?text=%20%20").is_err());
+    }
+
+    #[test]
+    fn ask_caps_prompt_length() {
+        let at_limit = "a".repeat(MAX_PROMPT_CHARS);
+        assert!(parse_str(&format!("seno://ask?text={at_limit}")).is_ok());
+        let over_limit = "a".repeat(MAX_PROMPT_CHARS + 1);
+        assert!(parse_str(&format!("seno://ask?text={over_limit}")).is_err());
+    }
+
+    #[test]
+    fn session_requires_a_plain_id() {
+        assert!(
+            matches!(parse_str("seno://session/abc-123"), Ok(Link::Session { id }) if id == "abc-123")
+        );
+        assert!(parse_str("seno://session/").is_err());
+        assert!(parse_str("seno://session/..%2Fetc").is_err());
+    }
+
+    #[test]
+    fn rejects_other_schemes_and_hosts() {
+        assert!(parse_str("https://ask?text=hi").is_err());
+        assert!(parse_str("seno://delete?text=hi").is_err());
+    }
+}
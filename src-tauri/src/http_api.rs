@@ -0,0 +1,199 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tauri::AppHandle;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::settings;
+use crate::{commands, injector, queue, responses, status, timing};
+
+// Largest request body accepted, to keep a misbehaving client from exhausting memory
+const MAX_BODY_BYTES: u64 = 1024 * 1024;
+
+// The old listener is closed on tiny_http's own accept thread shortly after its server is dropped
+const BIND_RETRIES: u32 = 20;
+const BIND_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+struct Running {
+    server: Arc<Server>,
+    // Holds the other reference to `server` until it stops accepting
+    thread: JoinHandle<()>,
+    port: u16,
+}
+
+static SERVER: Mutex<Option<Running>> = Mutex::new(None);
+// Read per request, so a new token applies without restarting the server
+static TOKEN: Mutex<String> = Mutex::new(String::new());
+
+#[derive(Deserialize)]
+struct SendRequest {
+    text: String,
+    #[serde(default)]
+    options: Option<Vec<injector::SendOption>>,
+}
+
+/// Starts, stops or restarts the server to match the current settings
+pub fn apply(app: &AppHandle) {
+    let config = settings::current().http_api;
+    *TOKEN.lock().unwrap() = config.token.clone();
+    let mut guard = SERVER.lock().unwrap();
+    if config.enabled
+        && guard
+            .as_ref()
+            .is_some_and(|running| running.port == config.port)
+    {
+        return;
+    }
+    if let Some(running) = guard.take() {
+        stop(running);
+    }
+    if !config.enabled {
+        return;
+    }
+
+    let server = match bind(config.port) {
+        Ok(server) => Arc::new(server),
+        Err(error) => {
+            eprintln!("Failed to start HTTP API on port {}: {error}", config.port);
+            return;
+        }
+    };
+    eprintln!("HTTP API listening on 127.0.0.1:{}", config.port);
+
+    let app = app.clone();
+    let listener = server.clone();
+    let thread = std::thread::spawn(move || {
+        for request in listener.incoming_requests() {
+            let app = app.clone();
+            // Sends and new chats wait on the pages, so don't hold up other clients
+            std::thread::spawn(move || handle(&app, request));
+        }
+    });
+    *guard = Some(Running {
+        server,
+        thread,
+        port: config.port,
+    });
+}
+
+// Dropping the last reference to the server is what closes its socket
+fn stop(running: Running) {
+    running.server.unblock();
+    if running.thread.join().is_err() {
+        eprintln!("HTTP API thread panicked");
+    }
+    drop(running.server);
+}
+
+fn bind(port: u16) -> Result<Server, String> {
+    let mut attempts = 0;
+    loop {
+        match Server::http(("127.0.0.1", port)) {
+            Ok(server) => return Ok(server),
+            Err(_) if attempts < BIND_RETRIES => {
+                attempts += 1;
+                std::thread::sleep(BIND_RETRY_INTERVAL);
+            }
+            Err(error) => return Err(error.to_string()),
+        }
+    }
+}
+
+fn handle(app: &AppHandle, mut request: Request) {
+    let token = TOKEN.lock().unwrap().clone();
+    let (code, body) = if !is_authorized(&request, &token) {
+        (401, json!({ "error": "Unauthorized" }))
+    } else {
+        match route(app, &mut request) {
+            Ok(Some(body)) => (200, body),
+            Ok(None) => (404, json!({ "error": "Not found" })),
+            Err(error) => (400, json!({ "error": error })),
+        }
+    };
+
+    let mut response = Response::from_string(body.to_string()).with_status_code(code);
+    if let Ok(header) = Header::from_bytes("Content-Type", "application/json") {
+        response = response.with_header(header);
+    }
+    if let Err(error) = request.respond(response) {
+        eprintln!("HTTP API response failed: {error}");
+    }
+}
+
+fn is_authorized(request: &Request, token: &str) -> bool {
+    if token.is_empty() {
+        return false;
+    }
+    let expected = format!("Bearer {token}");
+    request.headers().iter().any(|header| {
+        header.field.equiv("Authorization") && constant_time_eq(header.value.as_str(), &expected)
+    })
+}
+
+// Compares every byte regardless of where the first mismatch is, so response timing doesn't leak the token
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn route(app: &AppHandle, request: &mut Request) -> Result<Option<Value>, String> {
+    let path = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    let app = app.clone();
+    let value = match (request.method(), path.as_str()) {
+        (Method::Post, "/send") => {
+            let body: SendRequest = read_json(request)?;
+            let results = tauri::async_runtime::block_on(commands::send_to_all(
+                app,
+                body.text,
+                body.options,
+            ))?;
+            json!(results)
+        }
+        (Method::Post, "/new-chat") => {
+            let results = tauri::async_runtime::block_on(commands::new_chat_all(app))?;
+            json!(results)
+        }
+        (Method::Post, "/reload") => {
+            tauri::async_runtime::block_on(commands::reload_all(app))?;
+            json!({ "ok": true })
+        }
+        (Method::Post, "/stop") => {
+            let stopped = tauri::async_runtime::block_on(commands::stop_all(app))?;
+            json!({ "stopped": stopped })
+        }
+        (Method::Get, "/status") => {
+            let queued = crate::AI_SERVICES
+                .iter()
+                .map(|(label, _)| (label.to_string(), json!(queue::list(label).len())))
+                .collect::<serde_json::Map<_, _>>();
+            json!({ "providers": status::all(), "queued": queued, "timing": timing::all() })
+        }
+        (Method::Get, "/responses") => {
+            let responses = tauri::async_runtime::block_on(responses::last_all(&app));
+            json!(responses)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(value))
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(request: &mut Request) -> Result<T, String> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_string(&mut body)
+        .map_err(|e| e.to_string())?;
+    serde_json::from_str(&body).map_err(|e| e.to_string())
+}
@@ -0,0 +1,140 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter};
+
+use crate::launch::{MAX_PROMPT_FILE_BYTES, PROMPT_FILE_EXTENSIONS};
+use crate::settings;
+use crate::{commands, responses, ProviderToastPayload};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+// Files modified more recently than this may still be being written
+const SETTLE_TIME: Duration = Duration::from_secs(1);
+// Answers are written to `<prompt stem>.answers.md`, which is never picked up as a prompt
+const ANSWERS_SUFFIX: &str = ".answers";
+
+/// Starts the background thread that sends prompt files dropped into the inbox folder
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || {
+        // Files that failed once are left alone until the app restarts
+        let mut failed = HashSet::new();
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let inbox = settings::current().inbox;
+            if !inbox.enabled || inbox.dir.trim().is_empty() {
+                continue;
+            }
+            let Some(path) = next_prompt_file(Path::new(&inbox.dir), &failed) else {
+                continue;
+            };
+            let name = file_name(&path);
+            match process(&app, &path) {
+                Ok(()) => toast(&app, format!("Inbox: answered {name}")),
+                Err(error) => {
+                    eprintln!("Inbox failed for {}: {error}", path.display());
+                    toast(&app, format!("Inbox: {name} failed: {error}"));
+                    failed.insert(path);
+                }
+            }
+        }
+    });
+}
+
+fn answers_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{stem}{ANSWERS_SUFFIX}.md"))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+// Oldest unanswered prompt file in the folder
+fn next_prompt_file(dir: &Path, failed: &HashSet<PathBuf>) -> Option<PathBuf> {
+    let now = SystemTime::now();
+    let mut candidates = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let metadata = entry.metadata().ok()?;
+            let is_prompt = metadata.is_file()
+                && path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| {
+                        PROMPT_FILE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str())
+                    })
+                && !path
+                    .file_stem()
+                    .is_some_and(|stem| stem.to_string_lossy().ends_with(ANSWERS_SUFFIX));
+            if !is_prompt || failed.contains(&path) || answers_path(&path).exists() {
+                return None;
+            }
+            let modified = metadata.modified().ok()?;
+            let settled = now
+                .duration_since(modified)
+                .is_ok_and(|age| age >= SETTLE_TIME);
+            settled.then_some((modified, path))
+        })
+        .collect::<Vec<_>>();
+    candidates.sort();
+    candidates.into_iter().next().map(|(_, path)| path)
+}
+
+fn process(app: &AppHandle, path: &Path) -> Result<(), String> {
+    let size = std::fs::metadata(path).map_err(|e| e.to_string())?.len();
+    if size > MAX_PROMPT_FILE_BYTES {
+        return Err(format!("larger than {} KB", MAX_PROMPT_FILE_BYTES / 1024));
+    }
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    if text.trim().is_empty() {
+        return Err("empty prompt".to_string());
+    }
+
+    let labels = crate::AI_SERVICES
+        .iter()
+        .map(|(label, _)| *label)
+        .collect::<Vec<_>>();
+    tauri::async_runtime::block_on(async {
+        // Providers still answering something else get the prompt once they're done
+        let waits = commands::send_prompt(app, &labels, &text, &[])?
+            .into_iter()
+            .map(|(_, delivery)| tauri::async_runtime::spawn(delivery.wait_for_answer(None)))
+            .collect::<Vec<_>>();
+        for wait in waits {
+            let _ = wait.await;
+        }
+
+        let answers = format_answers(&file_name(path), &responses::last_all(app).await);
+        std::fs::write(answers_path(path), answers).map_err(|e| e.to_string())
+    })
+}
+
+fn format_answers(name: &str, responses: &[responses::ProviderResponse]) -> String {
+    let mut text = format!("# Answers to {name}\n\n");
+    for response in responses {
+        let answer = match (&response.text, &response.error) {
+            (Some(answer), _) => answer.clone(),
+            (None, Some(error)) => format!("_No answer captured: {error}_"),
+            (None, None) => "_No answer captured_".to_string(),
+        };
+        text.push_str(&format!(
+            "## {}\n\n{}\n\n",
+            crate::provider_name(&response.provider),
+            answer.trim()
+        ));
+    }
+    text.trim_end().to_string() + "\n"
+}
+
+fn toast(app: &AppHandle, message: String) {
+    let payload = ProviderToastPayload {
+        provider: "seno".to_string(),
+        message,
+    };
+    let _ = app.emit_to("titlebar", "provider-toast", payload);
+}
@@ -1,16 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+/// Composer features that can be switched on before a prompt is submitted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SendOption {
+    WebSearch,
+    DeepResearch,
+    Canvas,
+    ExtendedThinking,
+}
+
+// Resolves once find() returns an enabled element, instead of guessing how long the page takes to react
+const WAIT_FOR_ENABLED_SCRIPT: &str = r#"
+                const waitForEnabled = (find, timeout) => new Promise((resolve) => {
+                    const enabled = () => {
+                        const el = find();
+                        return el && !el.disabled && el.getAttribute('aria-disabled') !== 'true' ? el : null;
+                    };
+                    const found = enabled();
+                    if (found) return resolve(found);
+                    const observer = new MutationObserver(() => {
+                        const el = enabled();
+                        if (!el) return;
+                        observer.disconnect();
+                        clearTimeout(timer);
+                        resolve(el);
+                    });
+                    const timer = setTimeout(() => {
+                        observer.disconnect();
+                        resolve(null);
+                    }, timeout);
+                    observer.observe(document.body, {
+                        childList: true,
+                        subtree: true,
+                        attributes: true,
+                        attributeFilter: ['disabled', 'aria-disabled']
+                    });
+                });
+"#;
+
+// How long to wait for a send button to enable after inserting text
+const SEND_BUTTON_TIMEOUT_MS: u32 = 5000;
+
+// Escapes text for use inside a JS template literal
+fn escape_template(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('`', "\\`")
+        .replace('$', "\\$")
+}
+
 /// Returns script to send text to each AI service
 pub fn get_send_script(service: &str, text: &str) -> String {
-    let escaped_text = text
-        .replace('\\', "\\\\")
-        .replace('`', "\\`")
-        .replace('$', "\\$");
+    let escaped_text = escape_template(text);
+    let wait_for_enabled = WAIT_FOR_ENABLED_SCRIPT;
+    let timeout = SEND_BUTTON_TIMEOUT_MS;
 
     match service {
         "claude" => format!(
             r#"
             (function() {{
                 const text = `{escaped_text}`;
-
+{wait_for_enabled}
                 const pickEditor = () => {{
                     const candidates = Array.from(document.querySelectorAll('[contenteditable="true"]'))
                         .filter((el) => {{
@@ -46,20 +96,20 @@ pub fn get_send_script(service: &str, text: &str) -> String {
                         data: text
                     }}));
 
-                    setTimeout(() => {{
+                    const findSendButton = () => {{
                         const form = editor.closest('form');
                         const buttons = Array.from((form || document).querySelectorAll('button'));
-                        const sendBtn = buttons.find((button) => {{
+                        return buttons.find((button) => {{
                             const label = (button.getAttribute('aria-label') || '').toLowerCase();
                             if (!label) return false;
                             return label.includes('send')
                                 || label.includes('送信')
                                 || label.includes('メッセージを送信');
                         }});
-                        if (sendBtn && !sendBtn.disabled) {{
-                            sendBtn.click();
-                        }}
-                    }}, 100);
+                    }};
+                    waitForEnabled(findSendButton, {timeout}).then((sendBtn) => {{
+                        if (sendBtn) sendBtn.click();
+                    }});
                 }}
             }})();
             "#
@@ -69,7 +119,7 @@ pub fn get_send_script(service: &str, text: &str) -> String {
             r#"
             (function() {{
                 const text = `{escaped_text}`;
-
+{wait_for_enabled}
                 // ChatGPT uses #prompt-textarea
                 const textarea = document.querySelector('#prompt-textarea');
                 if (textarea) {{
@@ -91,14 +141,12 @@ pub fn get_send_script(service: &str, text: &str) -> String {
                     }}
 
                     // Find and click send button
-                    setTimeout(() => {{
-                        const sendBtn = document.querySelector('button[data-testid="send-button"]')
-                            || document.querySelector('form button[type="submit"]')
-                            || document.querySelector('button[aria-label*="Send"]');
-                        if (sendBtn && !sendBtn.disabled) {{
-                            sendBtn.click();
-                        }}
-                    }}, 100);
+                    const findSendButton = () => document.querySelector('button[data-testid="send-button"]')
+                        || document.querySelector('form button[type="submit"]')
+                        || document.querySelector('button[aria-label*="Send"]');
+                    waitForEnabled(findSendButton, {timeout}).then((sendBtn) => {{
+                        if (sendBtn) sendBtn.click();
+                    }});
                 }}
             }})();
             "#
@@ -108,7 +156,7 @@ pub fn get_send_script(service: &str, text: &str) -> String {
             r#"
             (function() {{
                 const text = `{escaped_text}`;
-
+{wait_for_enabled}
                 // Gemini uses rich-textarea or contenteditable
                 const editor = document.querySelector('.ql-editor[contenteditable="true"]')
                     || document.querySelector('rich-textarea [contenteditable="true"]')
@@ -129,14 +177,12 @@ pub fn get_send_script(service: &str, text: &str) -> String {
                     }}));
 
                     // Find and click send button
-                    setTimeout(() => {{
-                        const sendBtn = document.querySelector('button[aria-label*="Send"]')
-                            || document.querySelector('.send-button')
-                            || document.querySelector('button[mattooltip*="Send"]');
-                        if (sendBtn && !sendBtn.disabled) {{
-                            sendBtn.click();
-                        }}
-                    }}, 100);
+                    const findSendButton = () => document.querySelector('button[aria-label*="Send"]')
+                        || document.querySelector('.send-button')
+                        || document.querySelector('button[mattooltip*="Send"]');
+                    waitForEnabled(findSendButton, {timeout}).then((sendBtn) => {{
+                        if (sendBtn) sendBtn.click();
+                    }});
                 }}
             }})();
             "#
@@ -146,30 +192,501 @@ pub fn get_send_script(service: &str, text: &str) -> String {
     }
 }
 
-/// Returns script to trigger new chat via Cmd+Shift+O keyboard shortcut
-pub fn get_new_chat_script() -> &'static str {
-    r#"
-    (function() {
-        // Temporarily disable focus to prevent stealing
-        const originalFocus = HTMLElement.prototype.focus;
-        HTMLElement.prototype.focus = function() {};
-
-        const event = new KeyboardEvent('keydown', {
-            key: 'o',
-            code: 'KeyO',
-            keyCode: 79,
-            which: 79,
-            metaKey: true,
-            shiftKey: true,
-            bubbles: true,
-            cancelable: true
-        });
-        document.dispatchEvent(event);
-
-        // Re-enable focus after navigation settles
-        setTimeout(() => {
-            HTMLElement.prototype.focus = originalFocus;
-        }, 3000);
-    })();
+/// Selectors for the button that stops a response in progress; visible only while streaming
+pub fn stop_button_selectors(service: &str) -> &'static [&'static str] {
+    match service {
+        "claude" => &[
+            r#"button[aria-label*="Stop"]"#,
+            r#"button[aria-label*="停止"]"#,
+            r#"button[aria-label*="Cancel"]"#,
+        ],
+        "chatgpt" => &[
+            r#"button[data-testid="stop-button"]"#,
+            r#"button[aria-label*="Stop"]"#,
+            r#"button[aria-label*="停止"]"#,
+        ],
+        "gemini" => &[
+            r#"button[aria-label*="Stop"]"#,
+            r#"button[aria-label*="停止"]"#,
+            r#"button[mattooltip*="Stop"]"#,
+            r#"button[mattooltip*="停止"]"#,
+        ],
+        _ => &[],
+    }
+}
+
+/// Returns script that clicks the visible stop button, if any
+pub fn get_stop_script(service: &str) -> String {
+    let selectors = serde_json::to_string(stop_button_selectors(service)).unwrap_or_default();
+    format!(
+        r#"
+    (function() {{
+        for (const selector of {selectors}) {{
+            const button = Array.from(document.querySelectorAll(selector)).find((el) => {{
+                const rect = el.getBoundingClientRect();
+                return rect.width > 0 && rect.height > 0;
+            }});
+            if (button) {{
+                button.click();
+                return;
+            }}
+        }}
+    }})();
+    "#
+    )
+}
+
+/// Returns script that clicks the retry control on the latest response.
+/// Some sites open a menu from that control, so a matching menu item is clicked too.
+pub fn get_regenerate_script(service: &str) -> String {
+    let selectors = match service {
+        "claude" => {
+            r#"[
+            'button[data-testid="action-bar-retry"]',
+            'button[aria-label*="Retry"]',
+            'button[aria-label*="再試行"]'
+        ]"#
+        }
+        "chatgpt" => {
+            r#"[
+            'button[data-testid="regenerate-turn-action-button"]',
+            'button[aria-label*="Try again"]',
+            'button[aria-label*="Regenerate"]',
+            'button[aria-label*="再生成"]'
+        ]"#
+        }
+        "gemini" => {
+            r#"[
+            'regenerate-button button',
+            'button[aria-label*="Redo"]',
+            'button[mattooltip*="Redo"]',
+            'button[aria-label*="やり直す"]'
+        ]"#
+        }
+        _ => return String::new(),
+    };
+    let wait_for_enabled = WAIT_FOR_ENABLED_SCRIPT;
+
+    format!(
+        r#"
+    (function() {{
+{wait_for_enabled}
+        const isVisible = (el) => {{
+            const rect = el.getBoundingClientRect();
+            return rect.width > 0 && rect.height > 0;
+        }};
+        // The latest turn's control is the last one in the document
+        let target = null;
+        for (const selector of {selectors}) {{
+            const matches = Array.from(document.querySelectorAll(selector)).filter(isVisible);
+            if (matches.length) {{
+                target = matches[matches.length - 1];
+                break;
+            }}
+        }}
+        if (!target) return;
+        target.click();
+
+        const findMenuItem = () => Array.from(document.querySelectorAll('[role="menuitem"]'))
+            .filter(isVisible)
+            .find((el) => /try again|retry|regenerate|redo|再試行|再生成|やり直/i.test(el.textContent || ''));
+        waitForEnabled(findMenuItem, 1000).then((item) => {{
+            if (item) item.click();
+        }});
+    }})();
+    "#
+    )
+}
+
+// Outcomes reported by the edit script
+pub const EDIT_SUBMITTED: &str = "submitted";
+/// No edit control on the latest message, so a fresh send is the only option
+pub const EDIT_UNSUPPORTED: &str = "unsupported";
+pub const EDIT_NO_MESSAGE: &str = "noMessage";
+/// The edit form opened but could not be filled in or submitted
+pub const EDIT_FAILED: &str = "failed";
+
+/// Returns a `bridge::query` body that opens the edit control on the latest user message, replaces its
+/// text and resubmits. Resolves to one of the `EDIT_*` outcomes.
+pub fn get_edit_last_prompt_script(service: &str, text: &str) -> String {
+    // (user turn, container holding its controls, edit button, submit button label)
+    let (turn, container, edit, submit) = match service {
+        "claude" => (
+            r#"[data-testid="user-message"]"#,
+            r#"[data-test-render-count], .group"#,
+            r#"button[aria-label*="Edit"], button[aria-label*="編集"]"#,
+            r#"save|send|submit|保存|送信"#,
+        ),
+        "chatgpt" => (
+            r#"[data-message-author-role="user"]"#,
+            r#"[data-testid^="conversation-turn"], article"#,
+            r#"button[aria-label*="Edit message"], button[aria-label*="編集"]"#,
+            r#"send|save|submit|送信|保存"#,
+        ),
+        "gemini" => (
+            r#"user-query"#,
+            r#"user-query"#,
+            r#"button[aria-label*="Edit"], button[mattooltip*="Edit"], button[aria-label*="編集"]"#,
+            r#"update|send|save|更新|送信"#,
+        ),
+        _ => return format!("return '{EDIT_UNSUPPORTED}';"),
+    };
+    let escaped_text = escape_template(text);
+    let wait_for_enabled = WAIT_FOR_ENABLED_SCRIPT;
+    let timeout = SEND_BUTTON_TIMEOUT_MS;
+
+    format!(
+        r#"
+        const text = `{escaped_text}`;
+{wait_for_enabled}
+        const turns = document.querySelectorAll('{turn}');
+        const last = turns[turns.length - 1];
+        if (!last) return '{EDIT_NO_MESSAGE}';
+        const container = last.closest('{container}') || last.parentElement || last;
+
+        // Edit controls only render while the message is hovered
+        last.scrollIntoView({{ block: 'center' }});
+        for (const type of ['pointerover', 'pointerenter', 'mouseover', 'mouseenter']) {{
+            last.dispatchEvent(new MouseEvent(type, {{ bubbles: true }}));
+        }}
+
+        const findEditor = () => container.querySelector('textarea')
+            || container.querySelector('[contenteditable="true"]');
+        const findSubmit = () => Array.from(container.querySelectorAll('button'))
+            .find((button) => /{submit}/i.test(`${{button.textContent}} ${{button.getAttribute('aria-label') || ''}}`));
+
+        const editButton = await waitForEnabled(() => container.querySelector('{edit}'), {timeout});
+        if (!editButton) return '{EDIT_UNSUPPORTED}';
+        editButton.click();
+
+        const editor = await waitForEnabled(findEditor, {timeout});
+        if (!editor) return '{EDIT_FAILED}';
+        editor.focus();
+        if (editor instanceof HTMLTextAreaElement) {{
+            const setter = Object.getOwnPropertyDescriptor(HTMLTextAreaElement.prototype, 'value').set;
+            setter.call(editor, text);
+            editor.dispatchEvent(new Event('input', {{ bubbles: true }}));
+        }} else {{
+            document.execCommand('selectAll', false, null);
+            if (!document.execCommand('insertText', false, text)) {{
+                editor.textContent = text;
+            }}
+        }}
+
+        const submitButton = await waitForEnabled(findSubmit, {timeout});
+        if (!submitButton) return '{EDIT_FAILED}';
+        submitButton.click();
+        return '{EDIT_SUBMITTED}';
+    "#
+    )
+}
+
+/// Selectors for the button that shows the selected model and opens the model menu
+pub fn model_button_selectors(service: &str) -> &'static [&'static str] {
+    match service {
+        "claude" => &[
+            r#"button[data-testid="model-selector-dropdown"]"#,
+            r#"button[aria-label*="model" i]"#,
+        ],
+        "chatgpt" => &[
+            r#"button[data-testid="model-switcher-dropdown-button"]"#,
+            r#"button[aria-label*="Model selector"]"#,
+        ],
+        "gemini" => &[
+            r#"[data-test-id="bard-mode-menu-button"] button"#,
+            r#"button[data-test-id="bard-mode-menu-button"]"#,
+            r#"button.input-area-switch"#,
+        ],
+        _ => &[],
+    }
+}
+
+// Opens the model menu and resolves with its items, keyed by their first line of text
+fn open_model_menu_script(service: &str) -> String {
+    let selectors = serde_json::to_string(model_button_selectors(service)).unwrap_or_default();
+    let wait_for_enabled = WAIT_FOR_ENABLED_SCRIPT;
+    format!(
+        r#"
+{wait_for_enabled}
+        const itemSelector = '[role="menuitem"], [role="menuitemradio"], [role="option"]';
+        const itemName = (el) => (el.innerText || el.textContent || '').split('\n')[0].trim();
+        const closeMenu = () => document.body.dispatchEvent(
+            new KeyboardEvent('keydown', {{ key: 'Escape', code: 'Escape', bubbles: true }})
+        );
+        const button = {selectors}.map((selector) => document.querySelector(selector)).find(Boolean);
+        if (!button) return null;
+        button.click();
+        const first = await waitForEnabled(() => document.querySelector(itemSelector), 2000);
+        if (!first) return null;
+        const items = Array.from(document.querySelectorAll(itemSelector))
+            .map((el) => ({{ el, name: itemName(el) }}))
+            .filter((item) => item.name);
+"#
+    )
+}
+
+/// Returns a query body that lists the models offered in the provider's model menu
+pub fn get_list_models_script(service: &str) -> String {
+    format!(
+        r#"
+{open_menu}
+        closeMenu();
+        return items.map((item) => item.name);
+"#,
+        open_menu = open_model_menu_script(service)
+    )
+}
+
+/// Returns a query body that picks a model by name and resolves with the chosen entry, or null
+pub fn get_select_model_script(service: &str, model: &str) -> String {
+    let model = serde_json::to_string(&model.to_lowercase()).unwrap_or_default();
+    format!(
+        r#"
+{open_menu}
+        const wanted = {model};
+        const item = items.find((item) => item.name.toLowerCase() === wanted)
+            || items.find((item) => item.name.toLowerCase().includes(wanted));
+        if (!item) {{
+            closeMenu();
+            return null;
+        }}
+        item.el.click();
+        return item.name;
+"#,
+        open_menu = open_model_menu_script(service)
+    )
+}
+
+/// Pattern matching the label of the control behind an option, or None if the site has no such feature
+pub fn send_option_label(service: &str, option: SendOption) -> Option<&'static str> {
+    match (service, option) {
+        ("claude", SendOption::WebSearch) => Some("web search|ウェブ検索"),
+        ("claude", SendOption::DeepResearch) => Some("^research$|リサーチ"),
+        ("claude", SendOption::ExtendedThinking) => Some("extended thinking|拡張思考"),
+        ("chatgpt", SendOption::WebSearch) => Some("^search|web search|ウェブ検索"),
+        ("chatgpt", SendOption::DeepResearch) => Some("deep research|ディープ リサーチ"),
+        ("chatgpt", SendOption::Canvas) => Some("canvas|キャンバス"),
+        ("gemini", SendOption::DeepResearch) => Some("deep research|ディープ リサーチ"),
+        ("gemini", SendOption::Canvas) => Some("canvas|キャンバス"),
+        _ => None,
+    }
+}
+
+// Buttons that open the composer's tools menu when a toggle isn't shown directly
+fn tools_menu_selectors(service: &str) -> &'static [&'static str] {
+    match service {
+        "claude" => &[
+            r#"button[data-testid="input-menu-tools"]"#,
+            r#"button[aria-label*="tools" i]"#,
+        ],
+        "chatgpt" => &[
+            r#"button[data-testid="composer-plus-btn"]"#,
+            r#"#system-hint-button"#,
+            r#"button[aria-label*="tools" i]"#,
+        ],
+        "gemini" => &[r#"toolbox-drawer button"#, r#"button[aria-label*="Tools"]"#],
+        _ => &[],
+    }
+}
+
+/// Returns a query body that switches on the given composer toggles and resolves with the labels it couldn't find
+pub fn get_send_options_script(service: &str, labels: &[&str]) -> String {
+    let labels = serde_json::to_string(labels).unwrap_or_default();
+    let tools = serde_json::to_string(tools_menu_selectors(service)).unwrap_or_default();
+    let wait_for_enabled = WAIT_FOR_ENABLED_SCRIPT;
+    format!(
+        r#"
+{wait_for_enabled}
+        const isVisible = (el) => {{
+            const rect = el.getBoundingClientRect();
+            return rect.width > 0 && rect.height > 0;
+        }};
+        const isOn = (el) => el.getAttribute('aria-pressed') === 'true'
+            || el.getAttribute('aria-checked') === 'true'
+            || el.getAttribute('data-state') === 'on'
+            || el.getAttribute('data-state') === 'checked';
+        const closeMenu = () => document.body.dispatchEvent(
+            new KeyboardEvent('keydown', {{ key: 'Escape', code: 'Escape', bubbles: true }})
+        );
+        const findControl = (pattern, selector) => Array.from(document.querySelectorAll(selector))
+            .filter(isVisible)
+            .find((el) => pattern.test(`${{el.getAttribute('aria-label') || ''}} ${{(el.innerText || '').split('\n')[0]}}`.trim()));
+
+        const missing = [];
+        for (const label of {labels}) {{
+            const pattern = new RegExp(label, 'i');
+            const direct = findControl(pattern, 'form button, [data-testid*="composer"] button, .input-area button');
+            if (direct) {{
+                if (!isOn(direct)) direct.click();
+                continue;
+            }}
+
+            const toolsButton = {tools}.map((selector) => document.querySelector(selector)).find(Boolean);
+            if (!toolsButton) {{
+                missing.push(label);
+                continue;
+            }}
+            toolsButton.click();
+            const itemSelector = '[role="menuitem"], [role="menuitemcheckbox"], [role="menuitemradio"], [role="switch"]';
+            const item = await waitForEnabled(() => findControl(pattern, itemSelector), 2000);
+            if (!item) {{
+                closeMenu();
+                missing.push(label);
+                continue;
+            }}
+            const toggle = item.querySelector('[role="switch"], input[type="checkbox"]') || item;
+            if (!isOn(toggle) && !(toggle instanceof HTMLInputElement && toggle.checked)) item.click();
+            closeMenu();
+        }}
+        return missing;
+"#
+    )
+}
+
+/// Returns a query body that resolves with the text of the latest assistant message, or null
+pub fn get_last_response_script(service: &str) -> String {
+    let selectors = match service {
+        "claude" => {
+            r#"['.font-claude-response', '.font-claude-message', '[data-testid="assistant-message"]']"#
+        }
+        "chatgpt" => r#"['[data-message-author-role="assistant"]']"#,
+        "gemini" => r#"['model-response message-content', 'model-response', 'message-content']"#,
+        _ => return String::new(),
+    };
+    format!(
+        r#"
+        for (const selector of {selectors}) {{
+            const messages = document.querySelectorAll(selector);
+            const last = messages[messages.length - 1];
+            if (last) return (last.innerText || last.textContent || '').trim();
+        }}
+        return null;
+"#
+    )
+}
+
+/// Returns the URL that opens a fresh thread for each AI service
+pub fn get_new_chat_url(service: &str) -> Option<&'static str> {
+    match service {
+        "claude" => Some("https://claude.ai/new"),
+        "chatgpt" => Some("https://chatgpt.com/"),
+        "gemini" => Some("https://gemini.google.com/app"),
+        _ => None,
+    }
+}
+
+/// Whether the URL is an empty, not yet started thread
+pub fn is_new_chat_url(service: &str, url: &tauri::Url) -> bool {
+    let path = url.path().trim_end_matches('/');
+    match service {
+        "claude" => path == "/new",
+        "chatgpt" => path.is_empty(),
+        // Multi-account URLs look like /u/1/app
+        "gemini" => path == "/app" || (path.starts_with("/u/") && path.ends_with("/app")),
+        _ => false,
+    }
+}
+
+/// Returns a `bridge::query` body that resolves to whether the page shows an empty thread:
+/// no messages and nothing typed into the composer
+pub fn get_empty_thread_script(service: &str) -> String {
+    let messages = match service {
+        "claude" => r#"[data-testid="user-message"], .font-claude-message"#,
+        "chatgpt" => r#"[data-message-author-role]"#,
+        "gemini" => r#"user-query, model-response"#,
+        _ => return "return false;".to_string(),
+    };
+    format!(
+        r#"
+        if (document.querySelector('{messages}')) return false;
+        const composer = Array.from(document.querySelectorAll('textarea, [contenteditable="true"]'))
+            .find((el) => {{
+                const rect = el.getBoundingClientRect();
+                return rect.width > 0 && rect.height > 0;
+            }});
+        const draft = composer ? (composer.value ?? composer.innerText ?? '') : '';
+        return draft.trim() === '';
+    "#
+    )
+}
+
+/// Returns script that clicks the service's own new-chat control
+pub fn get_new_chat_script(service: &str) -> String {
+    let selectors = match service {
+        "claude" => {
+            r#"[
+            'a[href="/new"]',
+            'button[aria-label*="New chat"]',
+            'a[aria-label*="New chat"]',
+            'button[aria-label*="新しいチャット"]'
+        ]"#
+        }
+        "chatgpt" => {
+            r#"[
+            'a[data-testid="create-new-chat-button"]',
+            'button[data-testid="create-new-chat-button"]',
+            'button[aria-label*="New chat"]',
+            'a[aria-label*="New chat"]',
+            'button[aria-label*="新しいチャット"]'
+        ]"#
+        }
+        "gemini" => {
+            r#"[
+            '[data-test-id="new-chat-button"] button',
+            '[data-test-id="new-chat-button"] a',
+            'button[aria-label*="New chat"]',
+            'a[aria-label*="New chat"]',
+            'button[aria-label*="新しいチャット"]'
+        ]"#
+        }
+        _ => return String::new(),
+    };
+
+    format!(
+        r#"
+    (function() {{
+        const selectors = {selectors};
+        const target = selectors
+            .map((selector) => document.querySelector(selector))
+            .find((el) => el && !el.disabled);
+        if (target) {{
+            target.click();
+        }}
+    }})();
+    "#
+    )
+}
+
+/// Returns script to wipe page-level data for the current origin, then reload
+pub fn get_clear_data_script(storage: bool, indexed_db: bool, service_workers: bool) -> String {
+    format!(
+        r#"
+    (async function() {{
+        try {{
+            if ({storage}) {{
+                localStorage.clear();
+                sessionStorage.clear();
+                if ('caches' in window) {{
+                    const names = await caches.keys();
+                    await Promise.all(names.map(name => caches.delete(name)));
+                }}
+            }}
+            if ({indexed_db} && window.indexedDB && indexedDB.databases) {{
+                const databases = await indexedDB.databases();
+                await Promise.all(databases.map(db => new Promise((resolve) => {{
+                    const request = indexedDB.deleteDatabase(db.name);
+                    request.onsuccess = request.onerror = request.onblocked = resolve;
+                }})));
+            }}
+            if ({service_workers} && navigator.serviceWorker) {{
+                const registrations = await navigator.serviceWorker.getRegistrations();
+                await Promise.all(registrations.map(reg => reg.unregister()));
+            }}
+        }} catch (e) {{
+            console.error('Provider data clear error:', e);
+        }}
+        window.location.reload();
+    }})();
     "#
+    )
 }
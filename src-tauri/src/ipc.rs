@@ -0,0 +1,212 @@
+use interprocess::local_socket::prelude::*;
+use interprocess::local_socket::{
+    GenericFilePath, GenericNamespaced, Listener, ListenerOptions, Name, Stream,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::{commands, queue, responses, round, status};
+
+const SOCKET_FILE: &str = "seno.sock";
+// Same directory Tauri resolves as the app data dir from the bundle identifier
+const APP_IDENTIFIER: &str = "com.seno.viewer";
+
+/// A command sent from the `seno` CLI to the running app
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "camelCase")]
+pub enum Request {
+    Ask {
+        text: String,
+        /// Reply only after every provider has finished answering, with the answers
+        wait: bool,
+    },
+    New,
+    Reload,
+    Status,
+    /// The latest prompt and each provider's answer to it
+    LastRound,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Reply {
+    pub ok: bool,
+    pub error: Option<String>,
+    pub data: Value,
+}
+
+/// Socket name shared by the app and the CLI
+pub fn socket_name() -> io::Result<Name<'static>> {
+    if cfg!(windows) && GenericNamespaced::is_supported() {
+        return SOCKET_FILE.to_ns_name::<GenericNamespaced>();
+    }
+    socket_path()?.to_fs_name::<GenericFilePath>()
+}
+
+// A directory only this user can enter keeps other local users off the socket
+fn socket_path() -> io::Result<PathBuf> {
+    if let Some(dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+        return Ok(PathBuf::from(dir).join(SOCKET_FILE));
+    }
+    let dir = dirs::data_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No data directory"))?
+        .join(APP_IDENTIFIER);
+    create_private_dir(&dir)?;
+    Ok(dir.join(SOCKET_FILE))
+}
+
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)?;
+    // The directory may predate the socket with looser permissions
+    std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    std::fs::create_dir_all(dir)
+}
+
+/// Sends one request to the running app and waits for its reply
+pub fn send(request: &Request) -> io::Result<Reply> {
+    let mut stream = BufReader::new(Stream::connect(socket_name()?)?);
+    let mut line = serde_json::to_string(request)?;
+    line.push('\n');
+    stream.get_mut().write_all(line.as_bytes())?;
+
+    let mut reply = String::new();
+    stream.read_line(&mut reply)?;
+    Ok(serde_json::from_str(&reply)?)
+}
+
+/// Starts listening for CLI requests
+pub fn start(app: AppHandle) {
+    let listener = match listen() {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("Failed to start CLI socket: {error}");
+            return;
+        }
+    };
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let app = app.clone();
+                    // `ask --wait` holds its connection until every answer is in
+                    std::thread::spawn(move || {
+                        if let Err(error) = serve(&app, stream) {
+                            eprintln!("CLI request failed: {error}");
+                        }
+                    });
+                }
+                Err(error) => eprintln!("CLI connection failed: {error}"),
+            }
+        }
+    });
+}
+
+// Only a socket left behind by an instance that crashed is replaced; a live one is never taken over
+fn listen() -> io::Result<Listener> {
+    let create = || ListenerOptions::new().name(socket_name()?).create_sync();
+    match create() {
+        // Named pipes on Windows go away with their owner, so only socket files can be stale
+        Err(error) if error.kind() == io::ErrorKind::AddrInUse && !cfg!(windows) => {
+            if Stream::connect(socket_name()?).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    "another instance is listening",
+                ));
+            }
+            std::fs::remove_file(socket_path()?)?;
+            create()
+        }
+        result => result,
+    }
+}
+
+fn serve(app: &AppHandle, stream: Stream) -> io::Result<()> {
+    let mut stream = BufReader::new(stream);
+    let mut line = String::new();
+    stream.read_line(&mut line)?;
+
+    let result = serde_json::from_str::<Request>(&line)
+        .map_err(|e| e.to_string())
+        .and_then(|request| tauri::async_runtime::block_on(handle(app.clone(), request)));
+    let reply = match result {
+        Ok(data) => Reply {
+            ok: true,
+            error: None,
+            data,
+        },
+        Err(error) => Reply {
+            ok: false,
+            error: Some(error),
+            data: Value::Null,
+        },
+    };
+
+    let mut line = serde_json::to_string(&reply)?;
+    line.push('\n');
+    stream.get_mut().write_all(line.as_bytes())
+}
+
+async fn handle(app: AppHandle, request: Request) -> Result<Value, String> {
+    match request {
+        Request::Ask { text, wait } => {
+            if !wait {
+                return Ok(json!(commands::send_to_all(app, text, None).await?));
+            }
+            let labels = crate::AI_SERVICES
+                .iter()
+                .map(|(label, _)| *label)
+                .collect::<Vec<_>>();
+            let waits = commands::send_prompt(&app, &labels, &text, &[])?
+                .into_iter()
+                .map(|(_, delivery)| tauri::async_runtime::spawn(delivery.wait_for_answer(None)))
+                .collect::<Vec<_>>();
+            for wait in waits {
+                let _ = wait.await;
+            }
+            Ok(json!(responses::last_all(&app).await))
+        }
+        Request::New => Ok(json!(commands::new_chat_all(app).await?)),
+        Request::Reload => {
+            commands::reload_all(app).await?;
+            Ok(Value::Null)
+        }
+        Request::LastRound => {
+            let round = round::last().ok_or("Nothing has been sent yet")?;
+            let responses = responses::last_all(&app)
+                .await
+                .into_iter()
+                .filter(|response| round.providers.contains(&response.provider))
+                .collect::<Vec<_>>();
+            Ok(json!({
+                "prompt": round.prompt,
+                "sentAt": round.sent_at,
+                "responses": responses,
+            }))
+        }
+        Request::Status => {
+            let providers = crate::AI_SERVICES
+                .iter()
+                .map(|(label, _)| {
+                    json!({
+                        "provider": label,
+                        "status": status::get(label),
+                        "queued": queue::list(label).len(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            Ok(json!(providers))
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Url};
+
+const LAST_URLS_FILE: &str = "last_urls.json";
+
+// Profile id -> provider label -> last conversation URL
+type LastUrls = BTreeMap<String, BTreeMap<String, String>>;
+
+static LAST_URLS: Mutex<Option<LastUrls>> = Mutex::new(None);
+
+fn last_urls_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join(LAST_URLS_FILE))
+}
+
+/// Loads the last visited URLs from disk
+pub fn load(app: &AppHandle) {
+    let urls = last_urls_path(app)
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str::<LastUrls>(&json).ok())
+        .unwrap_or_default();
+    *LAST_URLS.lock().unwrap() = Some(urls);
+}
+
+pub fn get(profile_id: &str, label: &str) -> Option<Url> {
+    LAST_URLS
+        .lock()
+        .unwrap()
+        .as_ref()?
+        .get(profile_id)?
+        .get(label)
+        .and_then(|url| url.parse().ok())
+}
+
+/// Remembers a provider's current URL, persisting it when it changed
+pub fn record(app: &AppHandle, profile_id: &str, label: &str, url: &Url) -> Result<(), String> {
+    // Skip suspended pages, blank pages and sign-in flows
+    if url.scheme() != "https" || crate::is_oauth_popup(url) {
+        return Ok(());
+    }
+
+    let mut guard = LAST_URLS.lock().unwrap();
+    let urls = guard.get_or_insert_with(LastUrls::new);
+    let entry = urls.entry(profile_id.to_string()).or_default();
+    if entry.get(label).map(String::as_str) == Some(url.as_str()) {
+        return Ok(());
+    }
+    entry.insert(label.to_string(), url.to_string());
+
+    let path = last_urls_path(app)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(&*urls).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
@@ -0,0 +1,91 @@
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{deep_link, ProviderToastPayload};
+
+// Only plain-text prompt files are read, here and from the inbox folder; anything else is treated as a prompt
+pub const PROMPT_FILE_EXTENSIONS: [&str; 3] = ["md", "txt", "markdown"];
+// Guard against reading something huge into the input box
+pub const MAX_PROMPT_FILE_BYTES: u64 = 512 * 1024;
+// macOS passes -psn_... to apps started from Finder; any other argument is a prompt, file or link
+const IGNORED_ARG_PREFIXES: [&str; 1] = ["-psn_"];
+
+// Text waiting to be picked up by the input bar, which may not have loaded yet
+static PENDING_INPUT: Mutex<Option<String>> = Mutex::new(None);
+
+/// Handles the arguments of a launch: the app's own on startup, or ones forwarded from a second launch.
+/// Relative file paths are resolved against the launching process's working directory.
+pub fn handle(app: &AppHandle, args: &[String], cwd: &Path) {
+    focus_main_window(app);
+
+    let args = args
+        .iter()
+        .filter(|arg| {
+            !IGNORED_ARG_PREFIXES
+                .iter()
+                .any(|prefix| arg.starts_with(prefix))
+        })
+        .collect::<Vec<_>>();
+    if args.is_empty() {
+        return;
+    }
+
+    let mut prompts = Vec::new();
+    for arg in args {
+        if arg.starts_with(&format!("{}://", deep_link::SCHEME)) {
+            open_link(app, arg);
+        } else if let Some(text) = read_prompt_file(&cwd.join(arg)) {
+            prompts.push(text);
+        } else {
+            prompts.push(arg.clone());
+        }
+    }
+
+    // Prompts are prefilled rather than sent, so a stray launch never submits anything
+    if !prompts.is_empty() {
+        *PENDING_INPUT.lock().unwrap() = Some(prompts.join(" "));
+        let _ = app.emit_to("main", "pending-input", ());
+    }
+}
+
+/// Handles a seno:// link off the main thread, since it may wait on a confirmation dialog
+pub fn open_link(app: &AppHandle, url: &str) {
+    let app = app.clone();
+    let url = url.to_string();
+    tauri::async_runtime::spawn(async move {
+        if let Err(error) = deep_link::handle(&app, &url).await {
+            eprintln!("Failed to open {url}: {error}");
+            let payload = ProviderToastPayload {
+                provider: "seno".to_string(),
+                message: format!("Link not opened: {error}"),
+            };
+            let _ = app.emit_to("titlebar", "provider-toast", payload);
+        }
+    });
+}
+
+pub fn take_pending_input() -> Option<String> {
+    PENDING_INPUT.lock().unwrap().take()
+}
+
+fn focus_main_window(app: &AppHandle) {
+    let Some(window) = app.get_window("main") else {
+        return;
+    };
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+}
+
+fn read_prompt_file(path: &Path) -> Option<String> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    if !PROMPT_FILE_EXTENSIONS.contains(&extension.as_str()) {
+        return None;
+    }
+    let metadata = std::fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_PROMPT_FILE_BYTES {
+        return None;
+    }
+    std::fs::read_to_string(path).ok()
+}
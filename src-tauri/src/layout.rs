@@ -18,7 +18,7 @@ struct LayoutMetrics {
 }
 
 pub fn set_input_bar_height(height: f64) {
-    let clamped = height.max(INPUT_BAR_MIN).min(INPUT_BAR_MAX).round() as u32;
+    let clamped = height.clamp(INPUT_BAR_MIN, INPUT_BAR_MAX).round() as u32;
     INPUT_BAR_HEIGHT.store(clamped, Ordering::SeqCst);
 }
 
@@ -32,7 +32,9 @@ fn calculate_metrics(
     panel_count: usize,
 ) -> LayoutMetrics {
     let width = (physical_size.width as f64 / scale_factor).max(0.0).floor();
-    let height = (physical_size.height as f64 / scale_factor).max(0.0).floor();
+    let height = (physical_size.height as f64 / scale_factor)
+        .max(0.0)
+        .floor();
     let titlebar_height = TITLEBAR_HEIGHT;
     let input_bar_height = input_bar_height();
     let available_height = (height - titlebar_height - input_bar_height)
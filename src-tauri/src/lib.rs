@@ -1,17 +1,43 @@
+mod batch;
+mod bridge;
+mod budget;
+pub mod cli;
 mod commands;
+mod deep_link;
+mod http_api;
+mod inbox;
 mod injector;
+mod ipc;
+mod last_url;
+mod launch;
 mod layout;
+mod mcp;
+mod models;
+mod notifications;
+mod profile;
+mod queue;
+mod ready;
+mod responses;
+mod round;
+mod sessions;
+mod settings;
+mod status;
+mod timing;
+mod usage;
 
+use serde::Serialize;
 use tauri::{
-    menu::{MenuBuilder, MenuItemBuilder, PredefinedMenuItem, SubmenuBuilder},
-    webview::{NewWindowResponse, WebviewBuilder},
+    menu::{
+        CheckMenuItemBuilder, MenuBuilder, MenuItemBuilder, PredefinedMenuItem, SubmenuBuilder,
+    },
+    webview::{NewWindowResponse, PageLoadEvent, WebviewBuilder},
     Emitter, LogicalPosition, LogicalSize, Manager, PhysicalSize, Position, Size, WebviewUrl,
-    WebviewWindowBuilder, WindowEvent, TitleBarStyle,
+    WebviewWindowBuilder, WindowEvent,
 };
+use tauri_plugin_deep_link::DeepLinkExt;
 use tauri_plugin_opener::OpenerExt;
-use serde::Serialize;
 
-const AI_SERVICES: [(&str, &str); 3] = [
+pub(crate) const AI_SERVICES: [(&str, &str); 3] = [
     ("claude", "https://claude.ai/new"),
     ("chatgpt", "https://chat.openai.com/"),
     ("gemini", "https://gemini.google.com/app"),
@@ -19,6 +45,16 @@ const AI_SERVICES: [(&str, &str); 3] = [
 
 const TITLEBAR_VIEW_PATH: &str = "index.html?view=titlebar";
 
+const STOP_MENU_ITEM_PREFIX: &str = "stop:";
+const CLEAR_DATA_MENU_ITEM_PREFIX: &str = "clear_data:";
+
+const PROFILE_MENU_ID: &str = "profile";
+const PROFILE_MENU_ITEM_PREFIX: &str = "profile:";
+
+const SESSION_MENU_ID: &str = "sessions";
+const SESSION_MENU_ITEM_PREFIX: &str = "session:";
+const SESSION_MENU_LIMIT: usize = 15;
+
 // Prevent AI webviews from stealing focus during startup
 const FOCUS_GUARD_SCRIPT: &str = r#"
 (function() {
@@ -224,7 +260,7 @@ fn get_status_monitor_script(label: &str) -> String {
     format!(
         r#"
 (function() {{
-    if (window.__seno_status_monitor) return;
+    if (window.__seno_status_monitor || location.protocol === "data:") return;
     window.__seno_status_monitor = true;
 
     const provider = "{label}";
@@ -278,24 +314,50 @@ fn get_status_monitor_script(label: &str) -> String {
         return rect.width > 0 && rect.height > 0;
     }};
 
-    const isStreaming = () => {{
-        switch (provider) {{
-            case "claude":
-                return isVisible('button[aria-label*="Stop"]')
-                    || isVisible('button[aria-label*="停止"]')
-                    || isVisible('button[aria-label*="Cancel"]');
-            case "chatgpt":
-                return isVisible('button[data-testid="stop-button"]')
-                    || isVisible('button[aria-label*="Stop"]')
-                    || isVisible('button[aria-label*="停止"]');
-            case "gemini":
-                return isVisible('button[aria-label*="Stop"]')
-                    || isVisible('button[aria-label*="停止"]')
-                    || isVisible('button[mattooltip*="Stop"]')
-                    || isVisible('button[mattooltip*="停止"]');
-            default:
-                return false;
+    const stopSelectors = {stop_selectors};
+    const isStreaming = () => stopSelectors.some(isVisible);
+
+    const findEditor = () => {{
+        const selectors = {{
+            claude: ['div.ProseMirror[contenteditable="true"]', '[contenteditable="true"]'],
+            chatgpt: ['#prompt-textarea', 'textarea'],
+            gemini: ['.ql-editor[contenteditable="true"]', 'rich-textarea [contenteditable="true"]']
+        }}[provider] || [];
+        for (const selector of selectors) {{
+            const el = document.querySelector(selector);
+            if (el && el.getBoundingClientRect().width > 0) return el;
         }}
+        return null;
+    }};
+
+    // Answers a readiness request from Rust once the prompt editor exists
+    window.__seno_when_ready = (token) => {{
+        const signal = () => {{
+            const base = stripSuffix(document.title || "") || baseTitle;
+            document.title = `${{base}} [seno:ready:${{token}}]`;
+        }};
+        if (findEditor()) {{
+            signal();
+            return;
+        }}
+        const observer = new MutationObserver(() => {{
+            if (!findEditor()) return;
+            observer.disconnect();
+            signal();
+        }});
+        observer.observe(document.documentElement, {{ childList: true, subtree: true }});
+        setTimeout(() => observer.disconnect(), 10000);
+    }};
+
+    const modelSelectors = {model_selectors};
+    let lastModel = null;
+    const checkModel = () => {{
+        const button = modelSelectors.map((selector) => document.querySelector(selector)).find(Boolean);
+        if (!button) return;
+        const model = (button.innerText || button.textContent || "").split("\n")[0].trim();
+        if (!model || model === lastModel) return;
+        lastModel = model;
+        setStatusTitle(`model:${{encodeURIComponent(model)}}`);
     }};
 
     let lastStatus = null;
@@ -305,6 +367,7 @@ fn get_status_monitor_script(label: &str) -> String {
             lastStatus = status;
             setStatusTitle(status);
         }}
+        checkModel();
     }};
 
     const toastSelectors = [
@@ -351,20 +414,24 @@ fn get_status_monitor_script(label: &str) -> String {
     check();
 }})();
 "#,
-        label = label
+        label = label,
+        stop_selectors =
+            serde_json::to_string(injector::stop_button_selectors(label)).unwrap_or_default(),
+        model_selectors =
+            serde_json::to_string(injector::model_button_selectors(label)).unwrap_or_default()
     )
 }
 
 #[derive(Clone, Serialize)]
-struct ProviderStatusPayload {
-    provider: String,
-    status: String,
+pub(crate) struct ProviderStatusPayload {
+    pub provider: String,
+    pub status: String,
 }
 
 #[derive(Clone, Serialize)]
-struct ProviderToastPayload {
-    provider: String,
-    message: String,
+pub(crate) struct ProviderToastPayload {
+    pub provider: String,
+    pub message: String,
 }
 
 fn parse_status_from_title(title: &str) -> Option<&str> {
@@ -375,6 +442,18 @@ fn parse_toast_from_title(title: &str) -> Option<String> {
     parse_title_segment(title, " [seno:toast:").map(str::to_string)
 }
 
+fn parse_reply_from_title(title: &str) -> Option<&str> {
+    parse_title_segment(title, " [seno:reply:")
+}
+
+fn parse_model_from_title(title: &str) -> Option<String> {
+    parse_title_segment(title, " [seno:model:").and_then(bridge::decode)
+}
+
+fn parse_ready_from_title(title: &str) -> Option<u64> {
+    parse_title_segment(title, " [seno:ready:")?.parse().ok()
+}
+
 fn parse_title_segment<'a>(title: &'a str, marker: &str) -> Option<&'a str> {
     let start = title.rfind(marker)?;
     let value_start = start + marker.len();
@@ -387,6 +466,15 @@ fn parse_title_segment<'a>(title: &'a str, marker: &str) -> Option<&'a str> {
     }
 }
 
+pub(crate) fn provider_name(label: &str) -> &str {
+    match label {
+        "claude" => "Claude",
+        "chatgpt" => "ChatGPT",
+        "gemini" => "Gemini",
+        _ => label,
+    }
+}
+
 fn get_user_agent(label: &str) -> &'static str {
     match label {
         "gemini" => USER_AGENT_GEMINI,
@@ -394,40 +482,7 @@ fn get_user_agent(label: &str) -> &'static str {
     }
 }
 
-// Fixed UUIDs for session persistence (as byte arrays)
-const DATA_STORE_IDS: [(&str, [u8; 16]); 3] = [
-    (
-        "claude",
-        [
-            0xa1, 0xb2, 0xc3, 0xd4, 0xe5, 0xf6, 0x47, 0x89, 0xab, 0xcd, 0xef, 0x01, 0x23, 0x45,
-            0x67, 0x89,
-        ],
-    ),
-    (
-        "chatgpt",
-        [
-            0xb2, 0xc3, 0xd4, 0xe5, 0xf6, 0xa7, 0x48, 0x90, 0xbc, 0xde, 0xf0, 0x12, 0x34, 0x56,
-            0x78, 0x90,
-        ],
-    ),
-    (
-        "gemini",
-        [
-            0xc3, 0xd4, 0xe5, 0xf6, 0xa7, 0xb8, 0x49, 0x01, 0xcd, 0xef, 0x01, 0x23, 0x45, 0x67,
-            0x89, 0x01,
-        ],
-    ),
-];
-
-
-fn get_data_store_id(label: &str) -> Option<[u8; 16]> {
-    DATA_STORE_IDS
-        .iter()
-        .find(|(l, _)| *l == label)
-        .map(|(_, id)| *id)
-}
-
-fn is_oauth_popup(url: &tauri::Url) -> bool {
+pub(crate) fn is_oauth_popup(url: &tauri::Url) -> bool {
     let url_str = url.as_str().to_lowercase();
     if url_str.is_empty() || url_str == "about:blank" || url_str == "about:srcdoc" {
         return true;
@@ -475,32 +530,77 @@ fn is_oauth_popup(url: &tauri::Url) -> bool {
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        // Must come first so a second launch exits before setting anything else up
+        .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            launch::handle(
+                app,
+                argv.get(1..).unwrap_or_default(),
+                std::path::Path::new(&cwd),
+            );
+        }))
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_os::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_deep_link::init())
         .invoke_handler(tauri::generate_handler![
             commands::send_to_all,
+            commands::get_send_queue,
+            commands::cancel_queued_prompt,
             commands::reload_webview,
             commands::reload_all,
             commands::new_chat_all,
+            commands::regenerate_all,
+            commands::edit_last_prompt,
+            commands::list_models,
+            commands::select_model,
+            commands::get_current_models,
+            commands::get_last_responses,
+            commands::take_pending_input,
+            commands::stop_all,
+            commands::stop_provider,
             commands::update_input_height,
             commands::zoom_in,
             commands::zoom_out,
             commands::zoom_reset,
             commands::clear_cache_all,
+            commands::clear_provider_data,
             commands::refresh_gemini_session,
             commands::focus_input,
             commands::get_memory_usage,
+            commands::get_resource_usage,
             commands::get_display_settings,
             commands::set_display_settings,
+            commands::get_profiles,
+            commands::create_profile,
+            commands::rename_profile,
+            commands::delete_profile,
+            commands::switch_profile,
+            commands::list_sessions,
+            commands::open_session,
+            commands::rename_session,
+            commands::pin_session,
+            commands::delete_session,
+            commands::get_app_settings,
+            commands::set_app_settings,
+            commands::choose_inbox_folder,
+            commands::run_batch,
+            commands::cancel_batch,
+            commands::get_timing_stats,
         ])
         .setup(|app| {
             #[cfg(desktop)]
-            app.handle().plugin(tauri_plugin_updater::Builder::new().build())?;
+            app.handle()
+                .plugin(tauri_plugin_updater::Builder::new().build())?;
 
             let app_handle = app.handle().clone();
+            profile::load(&app_handle);
+            settings::load(&app_handle);
+            last_url::load(&app_handle);
+            sessions::load(&app_handle);
+
             let main_window = app.get_webview_window("main").unwrap();
             let window = main_window.as_ref().window();
             let scale_factor = window.scale_factor()?;
@@ -509,9 +609,11 @@ pub fn run() {
             let app_menu = SubmenuBuilder::new(app, "Seno")
                 .item(&PredefinedMenuItem::about(app, None, None)?)
                 .separator()
-                .item(&MenuItemBuilder::with_id("preferences", "Preferences...")
-                    .accelerator("CmdOrCtrl+,")
-                    .build(app)?)
+                .item(
+                    &MenuItemBuilder::with_id("preferences", "Preferences...")
+                        .accelerator("CmdOrCtrl+,")
+                        .build(app)?,
+                )
                 .separator()
                 .item(&PredefinedMenuItem::services(app, None)?)
                 .separator()
@@ -522,18 +624,26 @@ pub fn run() {
                 .item(&PredefinedMenuItem::quit(app, None)?)
                 .build()?;
             let view_menu = SubmenuBuilder::new(app, "View")
-                .item(&MenuItemBuilder::with_id("zoom_in", "Zoom In")
-                    .accelerator("CmdOrCtrl+Shift+=")
-                    .build(app)?)
-                .item(&MenuItemBuilder::with_id("zoom_in_alt", "Zoom In (Alt)")
-                    .accelerator("CmdOrCtrl+=")
-                    .build(app)?)
-                .item(&MenuItemBuilder::with_id("zoom_out", "Zoom Out")
-                    .accelerator("CmdOrCtrl+-")
-                    .build(app)?)
-                .item(&MenuItemBuilder::with_id("zoom_reset", "Actual Size")
-                    .accelerator("CmdOrCtrl+0")
-                    .build(app)?)
+                .item(
+                    &MenuItemBuilder::with_id("zoom_in", "Zoom In")
+                        .accelerator("CmdOrCtrl+Shift+=")
+                        .build(app)?,
+                )
+                .item(
+                    &MenuItemBuilder::with_id("zoom_in_alt", "Zoom In (Alt)")
+                        .accelerator("CmdOrCtrl+=")
+                        .build(app)?,
+                )
+                .item(
+                    &MenuItemBuilder::with_id("zoom_out", "Zoom Out")
+                        .accelerator("CmdOrCtrl+-")
+                        .build(app)?,
+                )
+                .item(
+                    &MenuItemBuilder::with_id("zoom_reset", "Actual Size")
+                        .accelerator("CmdOrCtrl+0")
+                        .build(app)?,
+                )
                 .build()?;
             let edit_menu = SubmenuBuilder::new(app, "Edit")
                 .item(&PredefinedMenuItem::undo(app, None)?)
@@ -544,42 +654,84 @@ pub fn run() {
                 .item(&PredefinedMenuItem::paste(app, None)?)
                 .item(&PredefinedMenuItem::select_all(app, None)?)
                 .build()?;
+            let mut clear_data_builder = SubmenuBuilder::new(app, "Clear Provider Data");
+            for (label, _) in AI_SERVICES.iter() {
+                clear_data_builder = clear_data_builder.item(
+                    &MenuItemBuilder::with_id(
+                        format!("{CLEAR_DATA_MENU_ITEM_PREFIX}{label}"),
+                        provider_name(label),
+                    )
+                    .build(app)?,
+                );
+            }
+            let clear_data_menu = clear_data_builder.build()?;
+            let mut stop_builder = SubmenuBuilder::new(app, "Stop");
+            for (label, _) in AI_SERVICES.iter() {
+                stop_builder = stop_builder.item(
+                    &MenuItemBuilder::with_id(
+                        format!("{STOP_MENU_ITEM_PREFIX}{label}"),
+                        provider_name(label),
+                    )
+                    .build(app)?,
+                );
+            }
+            let stop_menu = stop_builder.build()?;
             let chat_menu = SubmenuBuilder::new(app, "Chat")
-                .item(&MenuItemBuilder::with_id("new_chat_all", "New Chat (All)")
-                    .accelerator("CmdOrCtrl+N")
-                    .build(app)?)
-                .item(&MenuItemBuilder::with_id("reload_all", "Reload All")
-                    .accelerator("CmdOrCtrl+R")
-                    .build(app)?)
+                .item(
+                    &MenuItemBuilder::with_id("new_chat_all", "New Chat (All)")
+                        .accelerator("CmdOrCtrl+N")
+                        .build(app)?,
+                )
+                .item(
+                    &MenuItemBuilder::with_id("reload_all", "Reload All")
+                        .accelerator("CmdOrCtrl+R")
+                        .build(app)?,
+                )
+                .separator()
+                .item(
+                    &MenuItemBuilder::with_id("regenerate_all", "Regenerate All")
+                        .accelerator("CmdOrCtrl+Shift+R")
+                        .build(app)?,
+                )
+                .item(
+                    &MenuItemBuilder::with_id("stop_all", "Stop All")
+                        .accelerator("CmdOrCtrl+.")
+                        .build(app)?,
+                )
+                .item(&stop_menu)
                 .separator()
-                .item(&MenuItemBuilder::with_id("clear_cache", "Clear Cache")
-                    .accelerator("CmdOrCtrl+Shift+Delete")
-                    .build(app)?)
+                .item(&MenuItemBuilder::with_id("run_batch", "Run Batch…").build(app)?)
+                .separator()
+                .item(
+                    &MenuItemBuilder::with_id("clear_cache", "Clear Cache")
+                        .accelerator("CmdOrCtrl+Shift+Delete")
+                        .build(app)?,
+                )
+                .item(&clear_data_menu)
                 .build()?;
+            let session_menu = SubmenuBuilder::with_id(app, SESSION_MENU_ID, "Sessions").build()?;
+            let profile_menu = SubmenuBuilder::with_id(app, PROFILE_MENU_ID, "Profile").build()?;
             let menu = MenuBuilder::new(app)
-                .items(&[&app_menu, &edit_menu, &view_menu, &chat_menu])
+                .items(&[
+                    &app_menu,
+                    &edit_menu,
+                    &view_menu,
+                    &chat_menu,
+                    &session_menu,
+                    &profile_menu,
+                ])
                 .build()?;
             app.set_menu(menu)?;
+            refresh_session_menu(app.handle())?;
+            refresh_profile_menu(app.handle())?;
 
             app_handle.clone().on_menu_event(move |_app_handle, event| {
                 let app_handle = app_handle.clone();
                 let id = event.id().0.clone();
                 tauri::async_runtime::spawn(async move {
                     let result: Result<(), String> = match id.as_str() {
-                        "preferences" => {
-                            if let Some(window) = app_handle.get_webview_window("settings") {
-                                let _ = window.set_focus();
-                            } else {
-                                let _ = WebviewWindowBuilder::new(
-                                    &app_handle,
-                                    "settings",
-                                    WebviewUrl::App("settings.html".into()),
-                                )
-                                .title("Preferences")
-                                .inner_size(300.0, 260.0)
-                                .resizable(false)
-                                .build();
-                            }
+                        "preferences" | "manage_profiles" | "manage_sessions" => {
+                            open_preferences(&app_handle);
                             Ok(())
                         }
                         "zoom_in" => commands::zoom_in(app_handle).await.map(|_| ()),
@@ -587,8 +739,30 @@ pub fn run() {
                         "zoom_out" => commands::zoom_out(app_handle).await.map(|_| ()),
                         "zoom_reset" => commands::zoom_reset(app_handle).await.map(|_| ()),
                         "reload_all" => commands::reload_all(app_handle).await,
-                        "new_chat_all" => commands::new_chat_all(app_handle).await,
+                        "new_chat_all" => commands::new_chat_all(app_handle).await.map(|_| ()),
+                        "regenerate_all" => commands::regenerate_all(app_handle).await.map(|_| ()),
+                        "stop_all" => commands::stop_all(app_handle).await.map(|_| ()),
                         "clear_cache" => commands::clear_cache_all(app_handle).await,
+                        "run_batch" => batch::choose_and_run(&app_handle),
+                        id if id.starts_with(STOP_MENU_ITEM_PREFIX) => {
+                            let label = id[STOP_MENU_ITEM_PREFIX.len()..].to_string();
+                            commands::stop_provider(app_handle, label).await.map(|_| ())
+                        }
+                        id if id.starts_with(CLEAR_DATA_MENU_ITEM_PREFIX) => {
+                            let label = id[CLEAR_DATA_MENU_ITEM_PREFIX.len()..].to_string();
+                            let scopes = commands::ALL_DATA_SCOPES.to_vec();
+                            commands::clear_provider_data(app_handle, label, scopes)
+                                .await
+                                .map(|_| ())
+                        }
+                        id if id.starts_with(SESSION_MENU_ITEM_PREFIX) => {
+                            let session_id = id[SESSION_MENU_ITEM_PREFIX.len()..].to_string();
+                            commands::open_session(app_handle, session_id).await
+                        }
+                        id if id.starts_with(PROFILE_MENU_ITEM_PREFIX) => {
+                            let profile_id = id[PROFILE_MENU_ITEM_PREFIX.len()..].to_string();
+                            commands::switch_profile(app_handle, profile_id).await
+                        }
                         _ => Ok(()),
                     };
 
@@ -602,7 +776,7 @@ pub fn run() {
 
             #[cfg(target_os = "macos")]
             {
-                window.set_title_bar_style(TitleBarStyle::Overlay)?;
+                window.set_title_bar_style(tauri::TitleBarStyle::Overlay)?;
                 window.set_title("")?;
             }
 
@@ -621,80 +795,50 @@ pub fn run() {
             )?;
 
             // Add AI webviews as children of the main window
+            let active_profile = profile::active_profile();
             for (label, url) in AI_SERVICES.iter() {
-                let app_handle = app_handle.clone();
-                let opener_handle = app_handle.clone();
-                let mut builder =
-                    WebviewBuilder::new(*label, WebviewUrl::External(url.parse().unwrap()))
-                        .user_agent(get_user_agent(label))
-                        .focused(false)
-                        .on_new_window(move |url, _features| {
-                            let url_str = url.to_string();
-                            if is_oauth_popup(&url) {
-                                // Allow OAuth popups inside the app to preserve session cookies.
-                                return NewWindowResponse::Allow;
-                            }
-
-                            // Open target=_blank links in the default browser.
-                            match opener_handle.opener().open_url(url_str, None::<&str>) {
-                                Ok(_) => NewWindowResponse::Deny,
-                                Err(_) => NewWindowResponse::Allow,
-                            }
-                        });
-
-                // Prevent focus stealing during startup
-                builder = builder.initialization_script(FOCUS_GUARD_SCRIPT);
-
-                // Status monitoring script for streaming detection
-                builder = builder.initialization_script(get_status_monitor_script(label));
-
-                let status_app_handle = app_handle.clone();
-                let status_label = label.to_string();
-                builder = builder.on_document_title_changed(move |_webview, title| {
-                    if let Some(message) = parse_toast_from_title(&title) {
-                        let payload = ProviderToastPayload {
-                            provider: status_label.clone(),
-                            message,
-                        };
-                        let _ = status_app_handle.emit_to("titlebar", "provider-toast", payload);
-                        return;
-                    }
-                    if let Some(status) = parse_status_from_title(&title) {
-                        let payload = ProviderStatusPayload {
-                            provider: status_label.clone(),
-                            status: status.to_string(),
-                        };
-                        let _ = status_app_handle.emit_to("titlebar", "provider-status", payload);
-                    }
-                });
-
-                // Add initialization script for Gemini to bypass WebView detection
-                if *label == "gemini" {
-                    builder = builder.initialization_script(GEMINI_INIT_SCRIPT);
-                }
-
-                // Set data store identifier for session persistence (macOS)
-                #[cfg(target_os = "macos")]
-                if let Some(data_id) = get_data_store_id(label) {
-                    builder = builder.data_store_identifier(data_id);
-                }
-
-                let _webview = window.add_child(
-                    builder,
-                    Position::Logical(LogicalPosition { x: 0.0, y: 0.0 }),
-                    Size::Logical(LogicalSize {
-                        width: 1.0,
-                        height: 1.0,
-                    }),
-                )?;
+                add_ai_webview(&app_handle, &window, label, url, &active_profile)?;
             }
 
             let labels = ai_labels();
             layout::apply_layout(app.handle(), &labels, physical_size, scale_factor)
                 .map_err(|e| e.to_string())?;
 
+            budget::start(app.handle().clone());
+            http_api::apply(app.handle());
+            ipc::start(app.handle().clone());
+            inbox::start(app.handle().clone());
+
             // Show window after setup
             window.show()?;
+            let args = std::env::args().skip(1).collect::<Vec<_>>();
+            launch::handle(
+                app.handle(),
+                &args,
+                &std::env::current_dir().unwrap_or_default(),
+            );
+
+            // Linux and Windows deliver seno:// links as launch arguments, macOS as open-url events
+            #[cfg(any(target_os = "linux", windows))]
+            if let Err(error) = app.deep_link().register_all() {
+                eprintln!("Failed to register {}:// links: {error}", deep_link::SCHEME);
+            }
+            #[cfg(target_os = "macos")]
+            for url in app
+                .deep_link()
+                .get_current()
+                .ok()
+                .flatten()
+                .unwrap_or_default()
+            {
+                launch::open_link(app.handle(), url.as_str());
+            }
+            let link_app_handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                for url in event.urls() {
+                    launch::open_link(&link_app_handle, url.as_str());
+                }
+            });
 
             Ok(())
         })
@@ -731,6 +875,253 @@ pub fn run() {
         .expect("error while running tauri application");
 }
 
+fn add_ai_webview(
+    app_handle: &tauri::AppHandle,
+    window: &tauri::Window,
+    label: &str,
+    url: &str,
+    profile: &profile::Profile,
+) -> tauri::Result<()> {
+    let start_url = settings::current()
+        .restore_last_conversation
+        .then(|| last_url::get(&profile.id, label))
+        .flatten()
+        .unwrap_or_else(|| url.parse().unwrap());
+
+    let opener_handle = app_handle.clone();
+    let mut builder = WebviewBuilder::new(label, WebviewUrl::External(start_url))
+        .user_agent(get_user_agent(label))
+        .focused(false)
+        .on_new_window(move |url, _features| {
+            let url_str = url.to_string();
+            if is_oauth_popup(&url) {
+                // Allow OAuth popups inside the app to preserve session cookies.
+                return NewWindowResponse::Allow;
+            }
+
+            // Open target=_blank links in the default browser.
+            match opener_handle.opener().open_url(url_str, None::<&str>) {
+                Ok(_) => NewWindowResponse::Deny,
+                Err(_) => NewWindowResponse::Allow,
+            }
+        });
+
+    // Prevent focus stealing during startup
+    builder = builder.initialization_script(FOCUS_GUARD_SCRIPT);
+
+    // Status monitoring script for streaming detection
+    builder = builder.initialization_script(get_status_monitor_script(label));
+
+    // Track the current conversation; SPA navigations usually change the title too
+    let page_app_handle = app_handle.clone();
+    let page_profile_id = profile.id.clone();
+    builder = builder.on_page_load(move |webview, payload| {
+        if payload.event() == PageLoadEvent::Finished {
+            record_last_url(&page_app_handle, &page_profile_id, &webview);
+        }
+    });
+
+    let status_app_handle = app_handle.clone();
+    let status_profile_id = profile.id.clone();
+    let status_label = label.to_string();
+    builder = builder.on_document_title_changed(move |webview, title| {
+        record_last_url(&status_app_handle, &status_profile_id, &webview);
+        if let Some(message) = parse_toast_from_title(&title) {
+            let payload = ProviderToastPayload {
+                provider: status_label.clone(),
+                message,
+            };
+            let _ = status_app_handle.emit_to("titlebar", "provider-toast", payload);
+            return;
+        }
+        if let Some(reply) = parse_reply_from_title(&title) {
+            bridge::resolve(&webview, reply);
+            return;
+        }
+        if let Some(model) = parse_model_from_title(&title) {
+            models::set(&status_app_handle, &status_label, &model);
+            return;
+        }
+        if let Some(token) = parse_ready_from_title(&title) {
+            ready::resolve(&status_label, token);
+            return;
+        }
+        if let Some(status) = parse_status_from_title(&title) {
+            if status == budget::RESUME_STATUS {
+                if let Err(error) = budget::resume(&webview) {
+                    eprintln!("Failed to resume {status_label}: {error}");
+                }
+                return;
+            }
+            let previous = status::set(&status_label, status);
+            queue::on_status(&status_app_handle, &status_label, status);
+            let round = timing::on_status(&status_app_handle, &status_label, status);
+            notifications::on_status(
+                &status_app_handle,
+                &status_label,
+                previous.as_deref(),
+                status,
+                round,
+            );
+            let payload = ProviderStatusPayload {
+                provider: status_label.clone(),
+                status: status.to_string(),
+            };
+            let _ = status_app_handle.emit_to("titlebar", "provider-status", payload);
+        }
+    });
+
+    // Add initialization script for Gemini to bypass WebView detection
+    if label == "gemini" {
+        builder = builder.initialization_script(GEMINI_INIT_SCRIPT);
+    }
+
+    // Set data store identifier for session persistence (macOS)
+    #[cfg(target_os = "macos")]
+    if let Some(data_id) = profile.data_store_id(label) {
+        builder = builder.data_store_identifier(data_id);
+    }
+
+    // Set data directory for session persistence (Linux/Windows)
+    #[cfg(not(target_os = "macos"))]
+    {
+        builder = builder.data_directory(profile::data_directory(app_handle, profile, label)?);
+    }
+
+    window.add_child(
+        builder,
+        Position::Logical(LogicalPosition { x: 0.0, y: 0.0 }),
+        Size::Logical(LogicalSize {
+            width: 1.0,
+            height: 1.0,
+        }),
+    )?;
+
+    Ok(())
+}
+
+fn record_last_url(app: &tauri::AppHandle, profile_id: &str, webview: &tauri::Webview) {
+    let Ok(url) = webview.url() else {
+        return;
+    };
+    if let Err(error) = last_url::record(app, profile_id, webview.label(), &url) {
+        eprintln!("Failed to record last URL: {error}");
+    }
+    if url.scheme() == "https" && !is_oauth_popup(&url) {
+        if let Err(error) = sessions::record_url(app, profile_id, webview.label(), &url) {
+            eprintln!("Failed to record session URL: {error}");
+        }
+    }
+}
+
+/// Closes the AI webviews and recreates them with the active profile's data stores
+pub(crate) fn rebuild_ai_webviews(app: &tauri::AppHandle) -> Result<(), String> {
+    let window = app.get_window("main").ok_or("Main window not found")?;
+    let active_profile = profile::active_profile();
+
+    for (label, url) in AI_SERVICES.iter() {
+        if let Some(webview) = app.get_webview(label) {
+            webview.close().map_err(|e| e.to_string())?;
+        }
+        add_ai_webview(app, &window, label, url, &active_profile).map_err(|e| e.to_string())?;
+    }
+
+    let scale_factor = window.scale_factor().map_err(|e| e.to_string())?;
+    let physical_size = window.inner_size().map_err(|e| e.to_string())?;
+    let app_handle = app.clone();
+    window
+        .run_on_main_thread(move || {
+            if let Err(error) =
+                layout::apply_layout(&app_handle, &ai_labels(), physical_size, scale_factor)
+            {
+                eprintln!("Failed to apply layout: {error}");
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+/// Rebuilds the Profile menu from the stored profiles
+pub(crate) fn refresh_profile_menu(app: &tauri::AppHandle) -> tauri::Result<()> {
+    let Some(submenu) = app
+        .menu()
+        .and_then(|menu| menu.get(PROFILE_MENU_ID))
+        .and_then(|item| item.as_submenu().cloned())
+    else {
+        return Ok(());
+    };
+
+    for item in submenu.items()? {
+        submenu.remove(&item)?;
+    }
+
+    let settings = profile::current();
+    for entry in settings.profiles.iter() {
+        let item = CheckMenuItemBuilder::with_id(
+            format!("{PROFILE_MENU_ITEM_PREFIX}{}", entry.id),
+            &entry.name,
+        )
+        .checked(entry.id == settings.active)
+        .build(app)?;
+        submenu.append(&item)?;
+    }
+    submenu.append(&PredefinedMenuItem::separator(app)?)?;
+    submenu
+        .append(&MenuItemBuilder::with_id("manage_profiles", "Manage Profiles...").build(app)?)?;
+
+    Ok(())
+}
+
+/// Rebuilds the Sessions menu from the active profile's saved sessions
+pub(crate) fn refresh_session_menu(app: &tauri::AppHandle) -> tauri::Result<()> {
+    let Some(submenu) = app
+        .menu()
+        .and_then(|menu| menu.get(SESSION_MENU_ID))
+        .and_then(|item| item.as_submenu().cloned())
+    else {
+        return Ok(());
+    };
+
+    for item in submenu.items()? {
+        submenu.remove(&item)?;
+    }
+
+    let current = sessions::current_id();
+    let list = sessions::list(&profile::active_profile().id);
+    for session in list.iter().take(SESSION_MENU_LIMIT) {
+        let title = if session.pinned {
+            format!("★ {}", session.name)
+        } else {
+            session.name.clone()
+        };
+        let item = CheckMenuItemBuilder::with_id(
+            format!("{SESSION_MENU_ITEM_PREFIX}{}", session.id),
+            title,
+        )
+        .checked(current.as_ref() == Some(&session.id))
+        .build(app)?;
+        submenu.append(&item)?;
+    }
+    if !list.is_empty() {
+        submenu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+    submenu
+        .append(&MenuItemBuilder::with_id("manage_sessions", "Manage Sessions...").build(app)?)?;
+
+    Ok(())
+}
+
+fn open_preferences(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("settings") {
+        let _ = window.set_focus();
+    } else {
+        let _ = WebviewWindowBuilder::new(app, "settings", WebviewUrl::App("settings.html".into()))
+            .title("Preferences")
+            .inner_size(300.0, 720.0)
+            .resizable(false)
+            .build();
+    }
+}
+
 fn apply_layout_for_window(
     window: &tauri::Window,
     physical_size: PhysicalSize<u32>,
@@ -749,3 +1140,42 @@ fn apply_layout_for_window(
 fn ai_labels() -> Vec<&'static str> {
     AI_SERVICES.iter().map(|(label, _)| *label).collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reply_is_read_from_the_last_marker() {
+        let title = "Chat about [brackets] [seno:reply:12:0:2:%22long%20answer]";
+        assert_eq!(
+            parse_reply_from_title(title),
+            Some("12:0:2:%22long%20answer")
+        );
+    }
+
+    #[test]
+    fn reply_marker_is_not_mistaken_for_other_markers() {
+        let title = "Claude [seno:reply:3:0:1:null]";
+        assert_eq!(parse_toast_from_title(title), None);
+        assert_eq!(parse_model_from_title(title), None);
+        assert_eq!(parse_ready_from_title(title), None);
+        assert_eq!(parse_reply_from_title("Claude [seno:streaming]"), None);
+    }
+
+    #[test]
+    fn unterminated_or_empty_reply_is_ignored() {
+        assert_eq!(parse_reply_from_title("Claude [seno:reply:3:0:1:nu"), None);
+        assert_eq!(parse_reply_from_title("Claude [seno:reply: ]"), None);
+    }
+
+    #[test]
+    fn status_and_model_markers_are_parsed() {
+        assert_eq!(parse_status_from_title("Gemini [seno:idle]"), Some("idle"));
+        assert_eq!(
+            parse_model_from_title("ChatGPT [seno:model:GPT-4o%20mini]").as_deref(),
+            Some("GPT-4o mini")
+        );
+        assert_eq!(parse_ready_from_title("Claude [seno:ready:42]"), Some(42));
+    }
+}
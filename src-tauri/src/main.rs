@@ -2,5 +2,10 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // `seno ask ...` and friends talk to the running app instead of opening a window
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if let Some(code) = seno::cli::run(&args) {
+        std::process::exit(code);
+    }
     seno::run()
 }
@@ -0,0 +1,134 @@
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+
+use crate::cli;
+use crate::ipc::Request;
+
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Serves the Model Context Protocol over stdin/stdout, forwarding tool calls to the running app
+pub fn serve() -> Result<(), String> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+    for line in stdin.lock().lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => handle(&message),
+            Err(error) => Some(error_response(Value::Null, PARSE_ERROR, &error.to_string())),
+        };
+        // Notifications get no response
+        if let Some(response) = response {
+            writeln!(stdout, "{response}").map_err(|e| e.to_string())?;
+            stdout.flush().map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+fn handle(message: &Value) -> Option<Value> {
+    let id = message.get("id")?.clone();
+    let method = message["method"].as_str().unwrap_or_default();
+    let params = &message["params"];
+
+    let result = match method {
+        "initialize" => Ok(json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "seno", "version": env!("CARGO_PKG_VERSION") },
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools() })),
+        "tools/call" => call_tool(params),
+        _ => Err((METHOD_NOT_FOUND, format!("Unknown method: {method}"))),
+    };
+
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error_response(id, code, &message),
+    })
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn tools() -> Value {
+    json!([
+        {
+            "name": "ask_all",
+            "description": "Send a prompt to Claude, ChatGPT and Gemini in Seno, wait for all of them to finish, and return each answer.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "prompt": { "type": "string", "description": "Prompt to send" },
+                },
+                "required": ["prompt"],
+            },
+        },
+        {
+            "name": "get_last_round",
+            "description": "Return the latest prompt sent through Seno and each provider's current answer to it.",
+            "inputSchema": { "type": "object", "properties": {} },
+        },
+    ])
+}
+
+fn call_tool(params: &Value) -> Result<Value, (i64, String)> {
+    let arguments = &params["arguments"];
+    let request = match params["name"].as_str().unwrap_or_default() {
+        "ask_all" => {
+            let prompt = arguments["prompt"]
+                .as_str()
+                .filter(|prompt| !prompt.trim().is_empty())
+                .ok_or((INVALID_PARAMS, "Missing prompt".to_string()))?;
+            Request::Ask {
+                text: prompt.to_string(),
+                wait: true,
+            }
+        }
+        "get_last_round" => Request::LastRound,
+        name => return Err((INVALID_PARAMS, format!("Unknown tool: {name}"))),
+    };
+
+    // Tool failures are results the agent can read, not protocol errors
+    Ok(match cli::request(&request) {
+        Ok(data) => json!({
+            "content": [{ "type": "text", "text": format_answers(&data) }],
+        }),
+        Err(error) => json!({
+            "content": [{ "type": "text", "text": error }],
+            "isError": true,
+        }),
+    })
+}
+
+// Renders answers as one markdown section per provider
+fn format_answers(data: &Value) -> String {
+    let responses = data
+        .get("responses")
+        .unwrap_or(data)
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let mut text = String::new();
+    if let Some(prompt) = data["prompt"].as_str() {
+        text.push_str(&format!("Prompt: {prompt}\n\n"));
+    }
+    for response in responses {
+        let provider = response["provider"].as_str().unwrap_or_default();
+        let answer = response["text"].as_str().unwrap_or("(no answer captured)");
+        text.push_str(&format!(
+            "## {}\n\n{answer}\n\n",
+            crate::provider_name(provider)
+        ));
+    }
+    text.trim_end().to_string()
+}
@@ -0,0 +1,73 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{bridge, injector};
+
+// Opening the model menu is a UI round trip, so allow some slack
+const MENU_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Serialize)]
+pub struct ProviderModelPayload {
+    pub provider: String,
+    pub model: String,
+}
+
+// Model name last shown in each provider's model selector
+static CURRENT_MODELS: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+/// Records the model a provider page reports and tells the titlebar
+pub fn set(app: &AppHandle, label: &str, model: &str) {
+    let previous = CURRENT_MODELS
+        .lock()
+        .unwrap()
+        .insert(label.to_string(), model.to_string());
+    if previous.as_deref() == Some(model) {
+        return;
+    }
+    let payload = ProviderModelPayload {
+        provider: label.to_string(),
+        model: model.to_string(),
+    };
+    let _ = app.emit_to("titlebar", "provider-model", payload);
+}
+
+pub fn current() -> BTreeMap<String, String> {
+    CURRENT_MODELS.lock().unwrap().clone()
+}
+
+fn webview(app: &AppHandle, label: &str) -> Result<tauri::Webview, String> {
+    app.get_webview(label)
+        .ok_or_else(|| format!("Webview not found: {label}"))
+}
+
+/// Reads the entries of a provider's model menu
+pub async fn list(app: &AppHandle, label: &str) -> Result<Vec<String>, String> {
+    let webview = webview(app, label)?;
+    let models: Option<Vec<String>> = bridge::query(
+        &webview,
+        &injector::get_list_models_script(label),
+        MENU_TIMEOUT,
+    )
+    .await?;
+    models.ok_or_else(|| format!("Model menu not found for {}", crate::provider_name(label)))
+}
+
+/// Picks a model by (partial) name and returns the menu entry that was chosen
+pub async fn select(app: &AppHandle, label: &str, model: &str) -> Result<String, String> {
+    let webview = webview(app, label)?;
+    let selected: Option<String> = bridge::query(
+        &webview,
+        &injector::get_select_model_script(label, model),
+        MENU_TIMEOUT,
+    )
+    .await?;
+    selected.ok_or_else(|| {
+        format!(
+            "Model not available for {}: {model}",
+            crate::provider_name(label)
+        )
+    })
+}
@@ -0,0 +1,212 @@
+use chrono::{Local, NaiveTime};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::settings::{self, NotifyMode, QuietHours};
+use crate::timing::RoundTiming;
+use crate::{responses, status};
+
+// Keeps the notification body to a glanceable size
+const PREVIEW_CHARS: usize = 120;
+
+// Providers that finished since the last "all done" notification, with how long each took
+static FINISHED: Mutex<Vec<(String, Duration)>> = Mutex::new(Vec::new());
+// When each provider started streaming, for answers that weren't sent through Seno
+static STREAMING_SINCE: Mutex<BTreeMap<String, Instant>> = Mutex::new(BTreeMap::new());
+
+/// Called on every status change reported by a provider page, with the status it replaced
+/// and the timing of the round it completed, if any
+pub fn on_status(
+    app: &AppHandle,
+    label: &str,
+    previous: Option<&str>,
+    status: &str,
+    round: Option<RoundTiming>,
+) {
+    if status == "streaming" {
+        STREAMING_SINCE
+            .lock()
+            .unwrap()
+            .entry(label.to_string())
+            .or_insert_with(Instant::now);
+        return;
+    }
+    let streaming_since = STREAMING_SINCE.lock().unwrap().remove(label);
+    if previous != Some("streaming") || status != "idle" {
+        return;
+    }
+    let config = settings::current().notifications;
+    if !config.enabled {
+        return;
+    }
+
+    // Prefer the full round from sending to idle; fall back to how long it streamed
+    let duration = match (round, streaming_since) {
+        (Some(round), _) => Duration::from_millis(round.duration_ms),
+        (None, Some(since)) => since.elapsed(),
+        (None, None) => Duration::ZERO,
+    };
+    let finished = match config.mode {
+        NotifyMode::EachProvider => vec![(label.to_string(), duration)],
+        NotifyMode::AllDone => {
+            let mut finished = FINISHED.lock().unwrap();
+            finished.retain(|(finished, _)| finished != label);
+            finished.push((label.to_string(), duration));
+            let any_streaming = crate::AI_SERVICES
+                .iter()
+                .any(|(label, _)| status::get(label).as_deref() == Some("streaming"));
+            if any_streaming {
+                return;
+            }
+            std::mem::take(&mut *finished)
+        }
+    };
+
+    let longest = finished
+        .iter()
+        .map(|(_, duration)| *duration)
+        .max()
+        .unwrap_or_default();
+    if longest < Duration::from_secs(config.min_duration_secs)
+        || in_quiet_hours(&config.quiet_hours, Local::now().time())
+        || is_main_window_focused(app)
+    {
+        return;
+    }
+    let labels = finished
+        .into_iter()
+        .map(|(label, _)| label)
+        .collect::<Vec<_>>();
+    let sound = labels.iter().any(|label| !config.muted.contains(label));
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut lines = Vec::new();
+        for label in &labels {
+            let name = crate::provider_name(label);
+            let preview = if config.include_answer {
+                first_line(responses::last(&app, label).await.text.as_deref())
+            } else {
+                None
+            };
+            lines.push(match preview {
+                Some(preview) => format!("{name}: {preview}"),
+                None => format!("{name} finished"),
+            });
+        }
+        let title = match labels.as_slice() {
+            [label] => format!("{} finished", crate::provider_name(label)),
+            _ => "All AI responses completed".to_string(),
+        };
+        // Desktop notifications have no click callback, so clicking one only brings the window
+        // forward; the title names the panel instead of switching to it
+        if let Err(error) = show(&app, &title, &lines.join("\n"), sound) {
+            eprintln!("Failed to show notification: {error}");
+        }
+    });
+}
+
+// Quiet hours may span midnight, e.g. 22:00-08:00; equal start and end times mean none
+fn in_quiet_hours(quiet_hours: &QuietHours, now: NaiveTime) -> bool {
+    if !quiet_hours.enabled {
+        return false;
+    }
+    let parse = |time: &str| NaiveTime::parse_from_str(time.trim(), "%H:%M").ok();
+    let (Some(start), Some(end)) = (parse(&quiet_hours.start), parse(&quiet_hours.end)) else {
+        return false;
+    };
+    if start <= end {
+        start <= now && now < end
+    } else {
+        now >= start || now < end
+    }
+}
+
+fn is_main_window_focused(app: &AppHandle) -> bool {
+    app.get_window("main")
+        .and_then(|window| window.is_focused().ok())
+        .unwrap_or(false)
+}
+
+fn first_line(text: Option<&str>) -> Option<String> {
+    let line = text?.lines().map(str::trim).find(|line| !line.is_empty())?;
+    let mut preview = line.chars().take(PREVIEW_CHARS).collect::<String>();
+    if line.chars().count() > PREVIEW_CHARS {
+        preview.push('…');
+    }
+    Some(preview)
+}
+
+fn show(app: &AppHandle, title: &str, body: &str, sound: bool) -> Result<(), String> {
+    let mut builder = app.notification().builder().title(title).body(body);
+    if let Some(sound) = sound.then(sound_name).flatten() {
+        builder = builder.sound(sound);
+    }
+    builder.show().map_err(|e| e.to_string())
+}
+
+// Windows uses the OS default notification sound
+fn sound_name() -> Option<&'static str> {
+    if cfg!(target_os = "macos") {
+        Some("Ping")
+    } else if cfg!(target_os = "linux") {
+        Some("message-new-instant")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quiet_hours(start: &str, end: &str) -> QuietHours {
+        QuietHours {
+            enabled: true,
+            start: start.to_string(),
+            end: end.to_string(),
+        }
+    }
+
+    fn at(time: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(time, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn quiet_hours_within_one_day() {
+        let hours = quiet_hours("13:00", "14:30");
+        assert!(!in_quiet_hours(&hours, at("12:59")));
+        assert!(in_quiet_hours(&hours, at("13:00")));
+        assert!(in_quiet_hours(&hours, at("14:29")));
+        assert!(!in_quiet_hours(&hours, at("14:30")));
+    }
+
+    #[test]
+    fn quiet_hours_wrap_past_midnight() {
+        let hours = quiet_hours("22:00", "08:00");
+        assert!(in_quiet_hours(&hours, at("22:00")));
+        assert!(in_quiet_hours(&hours, at("23:59")));
+        assert!(in_quiet_hours(&hours, at("00:00")));
+        assert!(in_quiet_hours(&hours, at("07:59")));
+        assert!(!in_quiet_hours(&hours, at("08:00")));
+        assert!(!in_quiet_hours(&hours, at("21:59")));
+    }
+
+    #[test]
+    fn equal_start_and_end_is_never_quiet() {
+        let hours = quiet_hours("09:00", "09:00");
+        assert!(!in_quiet_hours(&hours, at("09:00")));
+        assert!(!in_quiet_hours(&hours, at("21:00")));
+    }
+
+    #[test]
+    fn disabled_or_invalid_quiet_hours_are_never_quiet() {
+        let mut hours = quiet_hours("22:00", "08:00");
+        hours.enabled = false;
+        assert!(!in_quiet_hours(&hours, at("23:00")));
+        assert!(!in_quiet_hours(&quiet_hours("late", "08:00"), at("23:00")));
+    }
+}
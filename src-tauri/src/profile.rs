@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::AI_SERVICES;
+
+pub const DEFAULT_PROFILE_ID: &str = "default";
+
+const PROFILES_FILE: &str = "profiles.json";
+
+// Fixed UUIDs for the default profile (as byte arrays), kept so existing sessions survive
+const DEFAULT_DATA_STORE_IDS: [(&str, [u8; 16]); 3] = [
+    (
+        "claude",
+        [
+            0xa1, 0xb2, 0xc3, 0xd4, 0xe5, 0xf6, 0x47, 0x89, 0xab, 0xcd, 0xef, 0x01, 0x23, 0x45,
+            0x67, 0x89,
+        ],
+    ),
+    (
+        "chatgpt",
+        [
+            0xb2, 0xc3, 0xd4, 0xe5, 0xf6, 0xa7, 0x48, 0x90, 0xbc, 0xde, 0xf0, 0x12, 0x34, 0x56,
+            0x78, 0x90,
+        ],
+    ),
+    (
+        "gemini",
+        [
+            0xc3, 0xd4, 0xe5, 0xf6, 0xa7, 0xb8, 0x49, 0x01, 0xcd, 0xef, 0x01, 0x23, 0x45, 0x67,
+            0x89, 0x01,
+        ],
+    ),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderStore {
+    pub provider: String,
+    pub data_store_id: [u8; 16],
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    pub stores: Vec<ProviderStore>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSettings {
+    pub active: String,
+    pub profiles: Vec<Profile>,
+}
+
+impl Profile {
+    fn new(name: &str) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            stores: AI_SERVICES
+                .iter()
+                .map(|(label, _)| ProviderStore {
+                    provider: label.to_string(),
+                    data_store_id: *uuid::Uuid::new_v4().as_bytes(),
+                })
+                .collect(),
+        }
+    }
+
+    fn default_profile() -> Self {
+        Self {
+            id: DEFAULT_PROFILE_ID.to_string(),
+            name: "Default".to_string(),
+            stores: DEFAULT_DATA_STORE_IDS
+                .iter()
+                .map(|(label, id)| ProviderStore {
+                    provider: label.to_string(),
+                    data_store_id: *id,
+                })
+                .collect(),
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub fn data_store_id(&self, label: &str) -> Option<[u8; 16]> {
+        self.stores
+            .iter()
+            .find(|store| store.provider == label)
+            .map(|store| store.data_store_id)
+    }
+}
+
+impl Default for ProfileSettings {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_PROFILE_ID.to_string(),
+            profiles: vec![Profile::default_profile()],
+        }
+    }
+}
+
+impl ProfileSettings {
+    pub fn active_profile(&self) -> Profile {
+        self.profiles
+            .iter()
+            .find(|profile| profile.id == self.active)
+            .cloned()
+            .unwrap_or_else(Profile::default_profile)
+    }
+}
+
+static PROFILE_SETTINGS: Mutex<Option<ProfileSettings>> = Mutex::new(None);
+
+fn profiles_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join(PROFILES_FILE))
+}
+
+/// Loads profiles from disk, falling back to the default profile
+pub fn load(app: &AppHandle) {
+    let settings = profiles_path(app)
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str::<ProfileSettings>(&json).ok())
+        .filter(|settings| !settings.profiles.is_empty())
+        .unwrap_or_default();
+    *PROFILE_SETTINGS.lock().unwrap() = Some(settings);
+}
+
+fn save(app: &AppHandle, settings: &ProfileSettings) -> Result<(), String> {
+    let path = profiles_path(app)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+fn update<T>(
+    app: &AppHandle,
+    f: impl FnOnce(&mut ProfileSettings) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut guard = PROFILE_SETTINGS.lock().unwrap();
+    let settings = guard.get_or_insert_with(ProfileSettings::default);
+    let result = f(settings)?;
+    save(app, settings)?;
+    Ok(result)
+}
+
+pub fn current() -> ProfileSettings {
+    PROFILE_SETTINGS
+        .lock()
+        .unwrap()
+        .get_or_insert_with(ProfileSettings::default)
+        .clone()
+}
+
+pub fn active_profile() -> Profile {
+    current().active_profile()
+}
+
+// Root of a profile's data directories; the default profile predates profiles and sits at the top
+#[cfg(not(target_os = "macos"))]
+fn data_root(app: &AppHandle, profile: &Profile) -> tauri::Result<std::path::PathBuf> {
+    let dir = app.path().app_data_dir()?.join("webview");
+    if profile.id == DEFAULT_PROFILE_ID {
+        return Ok(dir);
+    }
+    Ok(dir.join(&profile.id))
+}
+
+// Per-provider data directory for session persistence (WebKitGTK / WebView2)
+#[cfg(not(target_os = "macos"))]
+pub fn data_directory(
+    app: &AppHandle,
+    profile: &Profile,
+    label: &str,
+) -> tauri::Result<std::path::PathBuf> {
+    let dir = data_root(app, profile)?.join(label);
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Deletes the cookies, logins and caches of a profile whose webviews are closed
+pub async fn remove_data(app: &AppHandle, profile: &Profile) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    for store in &profile.stores {
+        app.remove_data_store(store.data_store_id)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let dir = data_root(app, profile).map_err(|e| e.to_string())?;
+        if dir.exists() {
+            std::fs::remove_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+pub fn create(app: &AppHandle, name: &str) -> Result<Profile, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name is empty".to_string());
+    }
+    update(app, |settings| {
+        let profile = Profile::new(name);
+        settings.profiles.push(profile.clone());
+        Ok(profile)
+    })
+}
+
+pub fn rename(app: &AppHandle, id: &str, name: &str) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name is empty".to_string());
+    }
+    update(app, |settings| {
+        let profile = settings
+            .profiles
+            .iter_mut()
+            .find(|profile| profile.id == id)
+            .ok_or_else(|| format!("Profile not found: {id}"))?;
+        profile.name = name.to_string();
+        Ok(())
+    })
+}
+
+/// Removes a profile from the list and returns it, so its data can be deleted
+pub fn delete(app: &AppHandle, id: &str) -> Result<Profile, String> {
+    if id == DEFAULT_PROFILE_ID {
+        return Err("The default profile cannot be deleted".to_string());
+    }
+    update(app, |settings| {
+        if settings.active == id {
+            return Err("The active profile cannot be deleted".to_string());
+        }
+        let index = settings
+            .profiles
+            .iter()
+            .position(|profile| profile.id == id)
+            .ok_or_else(|| format!("Profile not found: {id}"))?;
+        Ok(settings.profiles.remove(index))
+    })
+}
+
+/// Marks a profile as active. Returns false if it already was.
+pub fn set_active(app: &AppHandle, id: &str) -> Result<bool, String> {
+    update(app, |settings| {
+        if !settings.profiles.iter().any(|profile| profile.id == id) {
+            return Err(format!("Profile not found: {id}"));
+        }
+        if settings.active == id {
+            return Ok(false);
+        }
+        settings.active = id.to_string();
+        Ok(true)
+    })
+}
@@ -0,0 +1,453 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::oneshot;
+
+use crate::injector::{self, SendOption};
+use crate::{bridge, ready, status, timing, ProviderToastPayload};
+
+// A delivered prompt that never starts streaming stops blocking the queue after this long
+const START_TIMEOUT: Duration = Duration::from_secs(10);
+// Each toggle may need its tools menu opened first
+const OPTIONS_TIMEOUT: Duration = Duration::from_secs(8);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedPrompt {
+    pub id: u64,
+    pub text: String,
+    /// Toggles to switch on before submitting; only ones the provider supports
+    pub options: Vec<SendOption>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuePayload {
+    pub provider: String,
+    pub prompts: Vec<QueuedPrompt>,
+}
+
+#[derive(Default)]
+struct ProviderQueue {
+    prompts: VecDeque<QueuedPrompt>,
+    // Set while a prompt waits for the editor and its options, before it is submitted
+    sending: bool,
+    // Set when a prompt was handed to the page but streaming hasn't been seen yet
+    delivered_at: Option<Instant>,
+}
+
+impl ProviderQueue {
+    fn is_busy(&self, label: &str) -> bool {
+        status::get(label).as_deref() == Some("streaming")
+            || self.sending
+            || self
+                .delivered_at
+                .is_some_and(|at| at.elapsed() < START_TIMEOUT)
+    }
+
+    /// Takes the oldest prompt unless the provider is busy, marking the queue as sending
+    fn take_next(&mut self, label: &str) -> Option<QueuedPrompt> {
+        if self.is_busy(label) {
+            return None;
+        }
+        let prompt = self.prompts.pop_front();
+        self.sending = prompt.is_some();
+        prompt
+    }
+
+    /// Removes one prompt, or all of them when no id is given, and returns the removed ids
+    fn cancel(&mut self, id: Option<u64>) -> Vec<u64> {
+        let mut removed = Vec::new();
+        self.prompts.retain(|prompt| {
+            let keep = id.is_some_and(|id| prompt.id != id);
+            if !keep {
+                removed.push(prompt.id);
+            }
+            keep
+        });
+        removed
+    }
+}
+
+/// Outcome of one queued prompt, resolved once it is submitted to the page or dropped
+pub struct Delivery {
+    label: String,
+    /// True when the prompt waits behind an answer in progress instead of going out right away
+    pub queued: bool,
+    receiver: oneshot::Receiver<Result<(), String>>,
+}
+
+impl Delivery {
+    /// Waits until the prompt is submitted. Fails if its options couldn't be applied, the page
+    /// rejected the script, or the prompt was cancelled from the queue.
+    pub async fn wait(self) -> Result<(), String> {
+        self.receiver
+            .await
+            .unwrap_or_else(|_| Err("Removed from the queue".to_string()))
+    }
+
+    /// Waits for this prompt to be submitted and then answered, so an answer still in progress
+    /// when it was queued isn't mistaken for its own. Returns false if it wasn't sent, timed out
+    /// or was cancelled.
+    pub async fn wait_for_answer(self, cancel: Option<Arc<AtomicBool>>) -> bool {
+        let label = self.label.clone();
+        // A queued prompt goes out once the answer ahead of it finishes
+        match tokio::time::timeout(status::ANSWER_FINISH_TIMEOUT, self.wait()).await {
+            Ok(Ok(())) => status::wait_for_answer(&label, cancel).await,
+            Ok(Err(error)) => {
+                eprintln!("Prompt not delivered to {label}: {error}");
+                false
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+static QUEUES: Mutex<BTreeMap<String, ProviderQueue>> = Mutex::new(BTreeMap::new());
+// Waiting callers of queued prompts by id; dropping a sender tells its caller the prompt was cancelled
+static DELIVERIES: Mutex<BTreeMap<u64, oneshot::Sender<Result<(), String>>>> =
+    Mutex::new(BTreeMap::new());
+
+/// Queues a prompt for a provider and delivers it right away if the provider is free
+pub fn push(
+    app: &AppHandle,
+    label: &str,
+    text: &str,
+    options: &[SendOption],
+) -> Result<Delivery, String> {
+    let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+    let receiver = enqueue(
+        label,
+        QueuedPrompt {
+            id,
+            text: text.to_string(),
+            options: options.to_vec(),
+        },
+    );
+    deliver_next(app, label)?;
+    Ok(Delivery {
+        label: label.to_string(),
+        queued: list(label).iter().any(|prompt| prompt.id == id),
+        receiver,
+    })
+}
+
+fn enqueue(label: &str, prompt: QueuedPrompt) -> oneshot::Receiver<Result<(), String>> {
+    let (sender, receiver) = oneshot::channel();
+    DELIVERIES.lock().unwrap().insert(prompt.id, sender);
+    QUEUES
+        .lock()
+        .unwrap()
+        .entry(label.to_string())
+        .or_default()
+        .prompts
+        .push_back(prompt);
+    receiver
+}
+
+// Hands a prompt's outcome to its waiting Delivery
+fn resolve(id: u64, result: Result<(), String>) {
+    if let Some(sender) = DELIVERIES.lock().unwrap().remove(&id) {
+        let _ = sender.send(result);
+    }
+}
+
+/// Called on every status change reported by a provider page
+pub fn on_status(app: &AppHandle, label: &str, status: &str) {
+    match status {
+        "streaming" => {
+            if let Some(queue) = QUEUES.lock().unwrap().get_mut(label) {
+                queue.delivered_at = None;
+            }
+        }
+        "idle" => {
+            if let Err(error) = deliver_next(app, label) {
+                eprintln!("Failed to deliver queued prompt to {label}: {error}");
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Sends the oldest queued prompt if the provider is not busy
+fn deliver_next(app: &AppHandle, label: &str) -> Result<(), String> {
+    let prompt = {
+        let mut queues = QUEUES.lock().unwrap();
+        let Some(queue) = queues.get_mut(label) else {
+            return Ok(());
+        };
+        queue.take_next(label)
+    };
+    notify(app, label)?;
+
+    let Some(prompt) = prompt else {
+        return Ok(());
+    };
+    let webview = app
+        .get_webview(label)
+        .ok_or_else(|| format!("Webview not found: {label}"))?;
+    let app = app.clone();
+    let label = label.to_string();
+    tauri::async_runtime::spawn(async move {
+        if !ready::wait(&webview, ready::EDITOR_TIMEOUT).await {
+            eprintln!("Editor not ready for {label}, sending anyway");
+        }
+        // A prompt asked for with options it can't get isn't sent without them
+        let applied = if prompt.options.is_empty() {
+            Ok(())
+        } else {
+            apply_options(&webview, &label, &prompt.options).await
+        };
+        let result = match applied {
+            Ok(()) => {
+                let script = injector::get_send_script(&label, &prompt.text);
+                if let Some(queue) = QUEUES.lock().unwrap().get_mut(&label) {
+                    queue.sending = false;
+                    queue.delivered_at = Some(Instant::now());
+                }
+                let result = webview.eval(&script).map_err(|e| e.to_string());
+                match &result {
+                    Ok(()) => timing::on_sent(&label),
+                    Err(error) => eprintln!("Failed to send to {label}: {error}"),
+                }
+                schedule_recheck(&app, &label);
+                result
+            }
+            Err(error) => {
+                if let Some(queue) = QUEUES.lock().unwrap().get_mut(&label) {
+                    queue.sending = false;
+                }
+                if let Err(error) = deliver_next(&app, &label) {
+                    eprintln!("Failed to deliver queued prompt to {label}: {error}");
+                }
+                Err(error)
+            }
+        };
+        resolve(prompt.id, result);
+    });
+    Ok(())
+}
+
+// A prompt that never starts streaming (rejected send, reload, rate limit) brings no idle
+// transition, so look at the queue again once it stops counting as delivered
+fn schedule_recheck(app: &AppHandle, label: &str) {
+    let app = app.clone();
+    let label = label.to_string();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(START_TIMEOUT).await;
+        if let Err(error) = deliver_next(&app, &label) {
+            eprintln!("Failed to deliver queued prompt to {label}: {error}");
+        }
+    });
+}
+
+// Switches on the prompt's toggles; ones that can't be found are reported as a toast and returned
+async fn apply_options(
+    webview: &tauri::Webview,
+    label: &str,
+    options: &[SendOption],
+) -> Result<(), String> {
+    let labels = options
+        .iter()
+        .filter_map(|option| injector::send_option_label(label, *option))
+        .collect::<Vec<_>>();
+    let script = injector::get_send_options_script(label, &labels);
+    let missing = match bridge::query::<Vec<String>>(webview, &script, OPTIONS_TIMEOUT).await {
+        Ok(missing) if missing.is_empty() => return Ok(()),
+        Ok(missing) => missing
+            .iter()
+            .filter_map(|missing| {
+                options.iter().find(|option| {
+                    injector::send_option_label(label, **option) == Some(missing.as_str())
+                })
+            })
+            .map(|option| format!("{option:?}"))
+            .collect::<Vec<_>>()
+            .join(", "),
+        Err(error) => error,
+    };
+    eprintln!("Send options not applied for {label}: {missing}");
+    let payload = ProviderToastPayload {
+        provider: label.to_string(),
+        message: format!("Not sent, options not applied: {missing}"),
+    };
+    let _ = webview
+        .app_handle()
+        .emit_to("titlebar", "provider-toast", payload);
+    Err(format!("Send options not applied: {missing}"))
+}
+
+pub fn list(label: &str) -> Vec<QueuedPrompt> {
+    QUEUES
+        .lock()
+        .unwrap()
+        .get(label)
+        .map(|queue| queue.prompts.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Removes one queued prompt, or every queued prompt for the provider when no id is given
+pub fn cancel(app: &AppHandle, label: &str, id: Option<u64>) -> Result<(), String> {
+    remove(label, id);
+    notify(app, label)
+}
+
+// Dropping the senders tells the waiting callers their prompts were cancelled
+fn remove(label: &str, id: Option<u64>) {
+    let removed = match QUEUES.lock().unwrap().get_mut(label) {
+        Some(queue) => queue.cancel(id),
+        None => return,
+    };
+    let mut deliveries = DELIVERIES.lock().unwrap();
+    for id in removed {
+        deliveries.remove(&id);
+    }
+}
+
+fn notify(app: &AppHandle, label: &str) -> Result<(), String> {
+    let payload = QueuePayload {
+        provider: label.to_string(),
+        prompts: list(label),
+    };
+    app.emit_to("titlebar", "send-queue-changed", payload)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompt(id: u64) -> QueuedPrompt {
+        QueuedPrompt {
+            id,
+            text: format!("prompt {id}"),
+            options: Vec::new(),
+        }
+    }
+
+    fn queue(ids: &[u64]) -> ProviderQueue {
+        ProviderQueue {
+            prompts: ids.iter().copied().map(prompt).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn ids(queue: &ProviderQueue) -> Vec<u64> {
+        queue.prompts.iter().map(|prompt| prompt.id).collect()
+    }
+
+    fn delivery(label: &str, receiver: oneshot::Receiver<Result<(), String>>) -> Delivery {
+        Delivery {
+            label: label.to_string(),
+            queued: true,
+            receiver,
+        }
+    }
+
+    #[test]
+    fn take_next_is_first_in_first_out() {
+        let mut queue = queue(&[1, 2, 3]);
+        assert_eq!(
+            queue.take_next("test-fifo").map(|prompt| prompt.id),
+            Some(1)
+        );
+        queue.sending = false;
+        assert_eq!(
+            queue.take_next("test-fifo").map(|prompt| prompt.id),
+            Some(2)
+        );
+        assert_eq!(ids(&queue), [3]);
+    }
+
+    #[test]
+    fn take_next_waits_while_sending_or_delivered() {
+        let mut queue = queue(&[1, 2]);
+        assert!(queue.take_next("test-busy").is_some());
+        assert!(queue.sending);
+        assert!(queue.take_next("test-busy").is_none());
+
+        queue.sending = false;
+        queue.delivered_at = Some(Instant::now());
+        assert!(queue.take_next("test-busy").is_none());
+
+        queue.delivered_at = Instant::now().checked_sub(START_TIMEOUT);
+        assert_eq!(
+            queue.take_next("test-busy").map(|prompt| prompt.id),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn take_next_on_empty_queue_is_not_sending() {
+        let mut queue = queue(&[]);
+        assert!(queue.take_next("test-empty").is_none());
+        assert!(!queue.sending);
+    }
+
+    #[test]
+    fn cancel_removes_one_prompt_or_all() {
+        let mut queue = queue(&[1, 2, 3]);
+        assert_eq!(queue.cancel(Some(2)), [2]);
+        assert_eq!(ids(&queue), [1, 3]);
+        assert!(queue.cancel(Some(9)).is_empty());
+        assert_eq!(queue.cancel(None), [1, 3]);
+        assert!(queue.prompts.is_empty());
+    }
+
+    #[test]
+    fn queues_are_kept_per_provider() {
+        let _first = enqueue("test-a", prompt(101));
+        let _second = enqueue("test-b", prompt(102));
+        let _third = enqueue("test-a", prompt(103));
+        assert_eq!(
+            list("test-a")
+                .iter()
+                .map(|prompt| prompt.id)
+                .collect::<Vec<_>>(),
+            [101, 103]
+        );
+        assert_eq!(
+            list("test-b")
+                .iter()
+                .map(|prompt| prompt.id)
+                .collect::<Vec<_>>(),
+            [102]
+        );
+    }
+
+    #[test]
+    fn delivery_gets_the_send_result() {
+        let sent = delivery("test-result", enqueue("test-result", prompt(201)));
+        let failed = delivery("test-result", enqueue("test-result", prompt(202)));
+        resolve(201, Ok(()));
+        resolve(202, Err("Send options not applied: Search".to_string()));
+        assert_eq!(tauri::async_runtime::block_on(sent.wait()), Ok(()));
+        assert_eq!(
+            tauri::async_runtime::block_on(failed.wait()),
+            Err("Send options not applied: Search".to_string())
+        );
+    }
+
+    #[test]
+    fn cancelled_delivery_reports_removal() {
+        let cancelled = delivery("test-cancel", enqueue("test-cancel", prompt(301)));
+        let _kept = enqueue("test-cancel", prompt(302));
+        remove("test-cancel", Some(301));
+        assert_eq!(
+            tauri::async_runtime::block_on(cancelled.wait()),
+            Err("Removed from the queue".to_string())
+        );
+        assert_eq!(
+            list("test-cancel")
+                .iter()
+                .map(|prompt| prompt.id)
+                .collect::<Vec<_>>(),
+            [302]
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+/// Upper bound on waiting for a provider's prompt editor to render
+pub const EDITOR_TIMEOUT: Duration = Duration::from_secs(5);
+
+// The page may be mid-navigation when asked, so the request is repeated at this interval
+const RETRY_INTERVAL: Duration = Duration::from_millis(500);
+
+static NEXT_TOKEN: AtomicU64 = AtomicU64::new(1);
+
+// Request token -> (provider label, waiter)
+type Waiters = HashMap<u64, (String, oneshot::Sender<()>)>;
+
+static WAITERS: Mutex<Option<Waiters>> = Mutex::new(None);
+
+/// Waits until the provider page reports its editor is ready. Returns false on timeout.
+pub async fn wait(webview: &tauri::Webview, timeout: Duration) -> bool {
+    let label = webview.label().to_string();
+    let token = NEXT_TOKEN.fetch_add(1, Ordering::SeqCst);
+    let (tx, mut rx) = oneshot::channel();
+    WAITERS
+        .lock()
+        .unwrap()
+        .get_or_insert_with(Waiters::new)
+        .insert(token, (label, tx));
+
+    let started = Instant::now();
+    let ready = loop {
+        let script = format!("window.__seno_when_ready && window.__seno_when_ready({token});");
+        if webview.eval(&script).is_err() {
+            break false;
+        }
+        let remaining = timeout.saturating_sub(started.elapsed());
+        if remaining.is_zero() {
+            break false;
+        }
+        match tokio::time::timeout(remaining.min(RETRY_INTERVAL), &mut rx).await {
+            Ok(result) => break result.is_ok(),
+            Err(_) => continue,
+        }
+    };
+
+    if let Some(waiters) = WAITERS.lock().unwrap().as_mut() {
+        waiters.remove(&token);
+    }
+
+    ready
+}
+
+/// Called when a page signals readiness for a request token
+pub fn resolve(label: &str, token: u64) {
+    let mut guard = WAITERS.lock().unwrap();
+    let Some(waiters) = guard.as_mut() else {
+        return;
+    };
+    if waiters
+        .get(&token)
+        .is_some_and(|(pending, _)| pending == label)
+    {
+        if let Some((_, tx)) = waiters.remove(&token) {
+            let _ = tx.send(());
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::{bridge, injector, status};
+
+const QUERY_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderResponse {
+    pub provider: String,
+    pub status: Option<String>,
+    /// Text of the latest answer; partial while the provider is still streaming
+    pub text: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Reads the latest answer from one provider page
+pub async fn last(app: &AppHandle, label: &str) -> ProviderResponse {
+    let result = match app.get_webview(label) {
+        Some(webview) => {
+            bridge::query::<Option<String>>(
+                &webview,
+                &injector::get_last_response_script(label),
+                QUERY_TIMEOUT,
+            )
+            .await
+        }
+        None => Err(format!("Webview not found: {label}")),
+    };
+    let (text, error) = match result {
+        Ok(text) => (text, None),
+        Err(error) => (None, Some(error)),
+    };
+    ProviderResponse {
+        provider: label.to_string(),
+        status: status::get(label),
+        text,
+        error,
+    }
+}
+
+/// Reads the latest answer from every provider
+pub async fn last_all(app: &AppHandle) -> Vec<ProviderResponse> {
+    let handles = crate::AI_SERVICES
+        .iter()
+        .map(|(label, _)| {
+            let app = app.clone();
+            let label = label.to_string();
+            tauri::async_runtime::spawn(async move { last(&app, &label).await })
+        })
+        .collect::<Vec<_>>();
+
+    let mut responses = Vec::new();
+    for handle in handles {
+        if let Ok(response) = handle.await {
+            responses.push(response);
+        }
+    }
+    responses
+}
@@ -0,0 +1,35 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The latest prompt sent to the providers
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Round {
+    pub prompt: String,
+    pub providers: Vec<String>,
+    /// Milliseconds since the Unix epoch
+    pub sent_at: u64,
+}
+
+static LAST_ROUND: Mutex<Option<Round>> = Mutex::new(None);
+
+/// Milliseconds since the Unix epoch
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+pub fn start(labels: &[&str], prompt: &str) {
+    *LAST_ROUND.lock().unwrap() = Some(Round {
+        prompt: prompt.to_string(),
+        providers: labels.iter().map(|label| label.to_string()).collect(),
+        sent_at: now_millis(),
+    });
+}
+
+pub fn last() -> Option<Round> {
+    LAST_ROUND.lock().unwrap().clone()
+}
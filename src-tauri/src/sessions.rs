@@ -0,0 +1,400 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Url};
+
+use crate::injector;
+use crate::round::now_millis;
+
+const SESSIONS_FILE: &str = "sessions.json";
+const SESSION_NAME_MAX: usize = 60;
+const NEW_SESSION_NAME: &str = "New Session";
+// Unpinned sessions beyond this many are dropped, oldest first
+const MAX_UNPINNED_SESSIONS: usize = 50;
+
+/// A set of provider conversations that belong to the same comparison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    pub id: String,
+    pub name: String,
+    pub pinned: bool,
+    pub profile_id: String,
+    pub updated_at: u64,
+    /// Whether a prompt was sent in this session. Sessions saved before this was tracked
+    /// count as prompted so they're never reused.
+    #[serde(default = "default_prompted")]
+    pub prompted: bool,
+    /// Provider label -> conversation URL
+    pub urls: BTreeMap<String, String>,
+}
+
+fn default_prompted() -> bool {
+    true
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionStore {
+    current: Option<String>,
+    sessions: Vec<Session>,
+}
+
+static SESSIONS: Mutex<Option<SessionStore>> = Mutex::new(None);
+
+fn sessions_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join(SESSIONS_FILE))
+}
+
+/// Loads sessions from disk
+pub fn load(app: &AppHandle) {
+    let store = sessions_path(app)
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str::<SessionStore>(&json).ok())
+        .unwrap_or_default();
+    *SESSIONS.lock().unwrap() = Some(store);
+}
+
+fn save(app: &AppHandle, store: &SessionStore) -> Result<(), String> {
+    let path = sessions_path(app)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(store).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+fn update<T>(
+    app: &AppHandle,
+    f: impl FnOnce(&mut SessionStore) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut guard = SESSIONS.lock().unwrap();
+    let store = guard.get_or_insert_with(SessionStore::default);
+    let result = f(store)?;
+    save(app, store)?;
+    Ok(result)
+}
+
+fn find<'a>(store: &'a mut SessionStore, id: &str) -> Result<&'a mut Session, String> {
+    store
+        .sessions
+        .iter_mut()
+        .find(|session| session.id == id)
+        .ok_or_else(|| format!("Session not found: {id}"))
+}
+
+impl SessionStore {
+    fn new_session(&mut self, profile_id: &str) -> &mut Session {
+        // Reuse the current session if nothing happened in it yet. One that was browsed to
+        // a conversation keeps its URLs even without a prompt.
+        let reusable = self.current_index().filter(|index| {
+            let session = &self.sessions[*index];
+            !session.prompted && session.urls.is_empty() && session.profile_id == profile_id
+        });
+        if let Some(index) = reusable {
+            return &mut self.sessions[index];
+        }
+
+        let session = Session {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: NEW_SESSION_NAME.to_string(),
+            pinned: false,
+            profile_id: profile_id.to_string(),
+            updated_at: now_millis(),
+            prompted: false,
+            urls: BTreeMap::new(),
+        };
+        self.current = Some(session.id.clone());
+        self.sessions.insert(0, session);
+        self.prune();
+        &mut self.sessions[0]
+    }
+
+    fn current_index(&self) -> Option<usize> {
+        let current = self.current.as_ref()?;
+        self.sessions
+            .iter()
+            .position(|session| &session.id == current)
+    }
+
+    fn current_for(&mut self, profile_id: &str) -> &mut Session {
+        match self.current_index() {
+            Some(index) if self.sessions[index].profile_id == profile_id => {
+                &mut self.sessions[index]
+            }
+            _ => self.new_session(profile_id),
+        }
+    }
+
+    /// Marks the current session as prompted and names it after its first prompt, unless
+    /// it was renamed already. Returns true if the name changed.
+    fn note_prompt(&mut self, profile_id: &str, text: &str) -> bool {
+        let session = self.current_for(profile_id);
+        let first = !session.prompted;
+        session.prompted = true;
+        if !first || session.name != NEW_SESSION_NAME {
+            return false;
+        }
+        let line = text
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or_default()
+            .trim();
+        let mut name = line.chars().take(SESSION_NAME_MAX).collect::<String>();
+        if line.chars().count() > SESSION_NAME_MAX {
+            name.push('…');
+        }
+        if name.is_empty() {
+            return false;
+        }
+        session.name = name;
+        true
+    }
+
+    /// Makes a session of the given profile current and returns its URLs
+    fn open(&mut self, profile_id: &str, id: &str) -> Result<BTreeMap<String, String>, String> {
+        let session = find(self, id)?;
+        if session.profile_id != profile_id {
+            return Err(format!("Session {id} belongs to another profile"));
+        }
+        session.updated_at = now_millis();
+        let urls = session.urls.clone();
+        self.current = Some(id.to_string());
+        Ok(urls)
+    }
+
+    fn prune(&mut self) {
+        let current = self.current.clone();
+        let mut unpinned = 0;
+        self.sessions.retain(|session| {
+            if session.pinned || Some(&session.id) == current.as_ref() {
+                return true;
+            }
+            unpinned += 1;
+            unpinned <= MAX_UNPINNED_SESSIONS
+        });
+    }
+}
+
+/// Sessions for a profile, pinned first then most recent
+pub fn list(profile_id: &str) -> Vec<Session> {
+    let guard = SESSIONS.lock().unwrap();
+    let Some(store) = guard.as_ref() else {
+        return Vec::new();
+    };
+    let mut sessions = store
+        .sessions
+        .iter()
+        .filter(|session| session.profile_id == profile_id && !session.urls.is_empty())
+        .cloned()
+        .collect::<Vec<_>>();
+    sessions.sort_by(|a, b| {
+        b.pinned
+            .cmp(&a.pinned)
+            .then(b.updated_at.cmp(&a.updated_at))
+    });
+    sessions
+}
+
+pub fn current_id() -> Option<String> {
+    SESSIONS.lock().unwrap().as_ref()?.current.clone()
+}
+
+/// Starts a new session; called when every provider opens a new chat
+pub fn start(app: &AppHandle, profile_id: &str) -> Result<(), String> {
+    update(app, |store| {
+        store.new_session(profile_id);
+        Ok(())
+    })
+}
+
+/// Names the current session after its first prompt. Returns true if the name changed.
+pub fn note_prompt(app: &AppHandle, profile_id: &str, text: &str) -> Result<bool, String> {
+    update(app, |store| Ok(store.note_prompt(profile_id, text)))
+}
+
+/// Records a provider's conversation URL in the current session
+pub fn record_url(app: &AppHandle, profile_id: &str, label: &str, url: &Url) -> Result<(), String> {
+    // New chat (All) navigates before it starts the next session, so an empty chat must not
+    // overwrite the conversation saved in the previous one
+    if injector::is_new_chat_url(label, url) {
+        return Ok(());
+    }
+    {
+        let guard = SESSIONS.lock().unwrap();
+        let unchanged = guard
+            .as_ref()
+            .and_then(|store| store.current_index().map(|index| &store.sessions[index]))
+            .filter(|session| session.profile_id == profile_id)
+            .and_then(|session| session.urls.get(label))
+            .is_some_and(|current| current == url.as_str());
+        if unchanged {
+            return Ok(());
+        }
+    }
+
+    update(app, |store| {
+        let session = store.current_for(profile_id);
+        session.urls.insert(label.to_string(), url.to_string());
+        session.updated_at = now_millis();
+        Ok(())
+    })
+}
+
+/// Makes a saved session of the active profile current and returns its URLs
+pub fn open(
+    app: &AppHandle,
+    profile_id: &str,
+    id: &str,
+) -> Result<BTreeMap<String, String>, String> {
+    update(app, |store| store.open(profile_id, id))
+}
+
+pub fn rename(app: &AppHandle, id: &str, name: &str) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Session name is empty".to_string());
+    }
+    update(app, |store| {
+        find(store, id)?.name = name.to_string();
+        Ok(())
+    })
+}
+
+pub fn set_pinned(app: &AppHandle, id: &str, pinned: bool) -> Result<(), String> {
+    update(app, |store| {
+        find(store, id)?.pinned = pinned;
+        store.prune();
+        Ok(())
+    })
+}
+
+pub fn delete(app: &AppHandle, id: &str) -> Result<(), String> {
+    update(app, |store| {
+        store.sessions.retain(|session| session.id != id);
+        if store.current.as_deref() == Some(id) {
+            store.current = None;
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store_with_current(profile_id: &str) -> (SessionStore, String) {
+        let mut store = SessionStore::default();
+        let id = store.new_session(profile_id).id.clone();
+        (store, id)
+    }
+
+    #[test]
+    fn new_session_reuses_an_untouched_current_session() {
+        let (mut store, id) = store_with_current("default");
+        assert_eq!(store.new_session("default").id, id);
+        assert_eq!(store.sessions.len(), 1);
+    }
+
+    #[test]
+    fn new_session_keeps_a_prompted_session_named_like_a_new_one() {
+        let (mut store, id) = store_with_current("default");
+        store.note_prompt("default", "Compare these");
+        store.sessions[0].name = NEW_SESSION_NAME.to_string();
+        store.sessions[0]
+            .urls
+            .insert("claude".into(), "https://claude.ai/chat/1".into());
+
+        let next = store.new_session("default").id.clone();
+        assert_ne!(next, id);
+        let kept = store
+            .sessions
+            .iter()
+            .find(|session| session.id == id)
+            .unwrap();
+        assert_eq!(kept.urls.len(), 1);
+    }
+
+    #[test]
+    fn new_session_keeps_a_browsed_session_without_prompts() {
+        let (mut store, id) = store_with_current("default");
+        store.sessions[0]
+            .urls
+            .insert("claude".into(), "https://claude.ai/chat/1".into());
+
+        assert_ne!(store.new_session("default").id, id);
+        assert_eq!(store.sessions.len(), 2);
+        assert_eq!(store.sessions[1].urls.len(), 1);
+    }
+
+    #[test]
+    fn new_session_does_not_reuse_another_profiles_session() {
+        let (mut store, id) = store_with_current("work");
+        assert_ne!(store.new_session("default").id, id);
+    }
+
+    #[test]
+    fn note_prompt_names_only_after_the_first_prompt() {
+        let (mut store, _) = store_with_current("default");
+        assert!(store.note_prompt("default", "\n  First line  \nsecond"));
+        assert_eq!(store.sessions[0].name, "First line");
+        assert!(!store.note_prompt("default", "Another"));
+        assert_eq!(store.sessions[0].name, "First line");
+    }
+
+    #[test]
+    fn note_prompt_keeps_a_name_given_before_the_first_prompt() {
+        let (mut store, _) = store_with_current("default");
+        store.sessions[0].name = "Mine".to_string();
+        assert!(!store.note_prompt("default", "Prompt"));
+        assert_eq!(store.sessions[0].name, "Mine");
+        assert!(store.sessions[0].prompted);
+    }
+
+    #[test]
+    fn note_prompt_truncates_long_names() {
+        let (mut store, _) = store_with_current("default");
+        store.note_prompt("default", &"x".repeat(SESSION_NAME_MAX + 5));
+        assert_eq!(store.sessions[0].name.chars().count(), SESSION_NAME_MAX + 1);
+        assert!(store.sessions[0].name.ends_with('…'));
+    }
+
+    #[test]
+    fn open_rejects_sessions_of_other_profiles() {
+        let (mut store, work) = store_with_current("work");
+        store.note_prompt("work", "Prompt");
+        let default = store.new_session("default").id.clone();
+
+        assert!(store.open("default", &work).is_err());
+        assert_eq!(store.current.as_deref(), Some(default.as_str()));
+        assert!(store.open("work", &work).is_ok());
+        assert_eq!(store.current.as_deref(), Some(work.as_str()));
+    }
+
+    #[test]
+    fn sessions_saved_without_prompted_count_as_prompted() {
+        let json = r#"{"id":"1","name":"New Session","pinned":false,"profileId":"default","updatedAt":0,"urls":{}}"#;
+        let session: Session = serde_json::from_str(json).unwrap();
+        assert!(session.prompted);
+    }
+
+    #[test]
+    fn prune_drops_the_oldest_unpinned_sessions() {
+        let (mut store, first) = store_with_current("default");
+        store.sessions[0].pinned = true;
+        for _ in 0..=MAX_UNPINNED_SESSIONS {
+            store.note_prompt("default", "Prompt");
+            store.new_session("default");
+        }
+        assert!(store.sessions.iter().any(|session| session.id == first));
+        // The current session doesn't count towards the limit
+        let unpinned = store
+            .sessions
+            .iter()
+            .filter(|session| !session.pinned)
+            .count();
+        assert_eq!(unpinned, MAX_UNPINNED_SESSIONS + 1);
+    }
+}
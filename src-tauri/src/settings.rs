@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+const SETTINGS_FILE: &str = "settings.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BudgetAction {
+    Reload,
+    Suspend,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MemoryBudget {
+    pub enabled: bool,
+    /// Limit for a single provider's web processes, in MB
+    pub provider_limit_mb: Option<u64>,
+    /// Limit for the whole app, in MB
+    pub total_limit_mb: Option<u64>,
+    pub action: BudgetAction,
+}
+
+impl Default for MemoryBudget {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider_limit_mb: Some(1536),
+            total_limit_mb: None,
+            action: BudgetAction::Reload,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HttpApi {
+    pub enabled: bool,
+    /// Listens on 127.0.0.1 only
+    pub port: u16,
+    /// Expected as `Authorization: Bearer <token>`; generated when empty
+    pub token: String,
+}
+
+impl Default for HttpApi {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 47815,
+            token: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NotifyMode {
+    /// One notification as each provider finishes
+    EachProvider,
+    /// One notification once no provider is still answering
+    AllDone,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuietHours {
+    pub enabled: bool,
+    /// Local time as "HH:MM"; may be later than `end` to span midnight
+    pub start: String,
+    pub end: String,
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: "22:00".to_string(),
+            end: "08:00".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Notifications {
+    pub enabled: bool,
+    pub mode: NotifyMode,
+    /// Show the first line of each answer in the notification
+    pub include_answer: bool,
+    pub quiet_hours: QuietHours,
+    /// Skip answers that took less than this many seconds
+    pub min_duration_secs: u64,
+    /// Providers whose notifications play no sound
+    pub muted: Vec<String>,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            mode: NotifyMode::AllDone,
+            include_answer: true,
+            quiet_hours: QuietHours::default(),
+            min_duration_secs: 0,
+            muted: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Inbox {
+    pub enabled: bool,
+    /// Folder watched for `.md` and `.txt` prompt files
+    pub dir: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    pub memory_budget: MemoryBudget,
+    /// Reopen each provider on its last conversation instead of a fresh chat
+    pub restore_last_conversation: bool,
+    pub http_api: HttpApi,
+    pub inbox: Inbox,
+    pub notifications: Notifications,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            memory_budget: MemoryBudget::default(),
+            restore_last_conversation: true,
+            http_api: HttpApi::default(),
+            inbox: Inbox::default(),
+            notifications: Notifications::default(),
+        }
+    }
+}
+
+static APP_SETTINGS: Mutex<Option<AppSettings>> = Mutex::new(None);
+
+fn settings_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join(SETTINGS_FILE))
+}
+
+/// Loads settings from disk, falling back to defaults
+pub fn load(app: &AppHandle) {
+    let settings = settings_path(app)
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str::<AppSettings>(&json).ok())
+        .unwrap_or_default();
+    if settings.http_api.token.is_empty() {
+        // Keep the generated API token stable across launches
+        if let Err(error) = set(app, settings.clone()) {
+            eprintln!("Failed to save settings: {error}");
+            *APP_SETTINGS.lock().unwrap() = Some(settings);
+        }
+        return;
+    }
+    *APP_SETTINGS.lock().unwrap() = Some(settings);
+}
+
+pub fn current() -> AppSettings {
+    APP_SETTINGS
+        .lock()
+        .unwrap()
+        .get_or_insert_with(AppSettings::default)
+        .clone()
+}
+
+pub fn set(app: &AppHandle, mut settings: AppSettings) -> Result<(), String> {
+    if settings.http_api.token.is_empty() {
+        settings.http_api.token = uuid::Uuid::new_v4().simple().to_string();
+    }
+    let path = settings_path(app)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())?;
+    *APP_SETTINGS.lock().unwrap() = Some(settings);
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// The status monitor itself only checks once a second
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+// A provider may sit behind a slow page load before it starts answering
+pub const ANSWER_START_TIMEOUT: Duration = Duration::from_secs(60);
+pub const ANSWER_FINISH_TIMEOUT: Duration = Duration::from_secs(600);
+
+// Last status reported by each provider's status monitor ("streaming", "idle", ...)
+static PROVIDER_STATUS: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+/// Records a provider status. Returns the previous one, if any.
+pub fn set(label: &str, status: &str) -> Option<String> {
+    PROVIDER_STATUS
+        .lock()
+        .unwrap()
+        .insert(label.to_string(), status.to_string())
+}
+
+pub fn get(label: &str) -> Option<String> {
+    PROVIDER_STATUS.lock().unwrap().get(label).cloned()
+}
+
+pub fn is_idle(label: &str) -> bool {
+    get(label).as_deref() == Some("idle")
+}
+
+pub fn all() -> BTreeMap<String, String> {
+    PROVIDER_STATUS.lock().unwrap().clone()
+}
+
+/// Waits for a provider to start answering and then go idle again, or until `cancel` is set.
+/// Returns false on timeout or cancellation.
+pub async fn wait_for_answer(label: &str, cancel: Option<Arc<AtomicBool>>) -> bool {
+    let cancelled = || {
+        cancel
+            .as_ref()
+            .is_some_and(|cancel| cancel.load(Ordering::SeqCst))
+    };
+    let started = Instant::now();
+    while get(label).as_deref() != Some("streaming") {
+        if started.elapsed() >= ANSWER_START_TIMEOUT || cancelled() {
+            return false;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    let streaming = Instant::now();
+    while get(label).as_deref() == Some("streaming") {
+        if streaming.elapsed() >= ANSWER_FINISH_TIMEOUT || cancelled() {
+            return false;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    true
+}
@@ -0,0 +1,171 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+
+use crate::round;
+
+// Rounds kept per provider for the rolling statistics
+const HISTORY_LEN: usize = 50;
+
+/// Timestamps of one provider's answer to one prompt
+#[derive(Debug, Clone, Copy)]
+struct PendingRound {
+    sent: Instant,
+    sent_at: u64,
+    first_streaming: Option<Instant>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoundTiming {
+    /// Milliseconds since the Unix epoch
+    pub sent_at: u64,
+    /// Time from sending to the first streaming status
+    pub ttft_ms: u64,
+    /// Time from sending to idle again
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimingStats {
+    pub provider: String,
+    pub last: Option<RoundTiming>,
+    pub rounds: usize,
+    pub avg_ttft_ms: Option<u64>,
+    pub median_ttft_ms: Option<u64>,
+    pub avg_duration_ms: Option<u64>,
+    pub median_duration_ms: Option<u64>,
+}
+
+#[derive(Default)]
+struct ProviderTiming {
+    pending: Option<PendingRound>,
+    history: VecDeque<RoundTiming>,
+}
+
+static TIMINGS: Mutex<BTreeMap<String, ProviderTiming>> = Mutex::new(BTreeMap::new());
+
+/// Called when a prompt is submitted to a provider page
+pub fn on_sent(label: &str) {
+    TIMINGS
+        .lock()
+        .unwrap()
+        .entry(label.to_string())
+        .or_default()
+        .pending = Some(PendingRound {
+        sent: Instant::now(),
+        sent_at: round::now_millis(),
+        first_streaming: None,
+    });
+}
+
+/// Called on every status change reported by a provider page. Returns the round it completed, if any.
+pub fn on_status(app: &AppHandle, label: &str, status: &str) -> Option<RoundTiming> {
+    let finished = {
+        let mut timings = TIMINGS.lock().unwrap();
+        let timing = timings.get_mut(label)?;
+        let pending = timing.pending.as_mut()?;
+        match (status, pending.first_streaming) {
+            ("streaming", None) => {
+                pending.first_streaming = Some(Instant::now());
+                None
+            }
+            // Idle before any streaming means the page hasn't picked up the prompt yet
+            ("idle", Some(first_streaming)) => {
+                let round = RoundTiming {
+                    sent_at: pending.sent_at,
+                    ttft_ms: first_streaming.duration_since(pending.sent).as_millis() as u64,
+                    duration_ms: pending.sent.elapsed().as_millis() as u64,
+                };
+                timing.pending = None;
+                if timing.history.len() == HISTORY_LEN {
+                    timing.history.pop_front();
+                }
+                timing.history.push_back(round);
+                Some(round)
+            }
+            _ => None,
+        }
+    };
+
+    if finished.is_some() {
+        let _ = app.emit_to("titlebar", "provider-timing", stats(label));
+    }
+    finished
+}
+
+/// Latest round and rolling statistics for one provider
+pub fn stats(label: &str) -> TimingStats {
+    let timings = TIMINGS.lock().unwrap();
+    let history = timings
+        .get(label)
+        .map(|timing| timing.history.iter().copied().collect::<Vec<_>>())
+        .unwrap_or_default();
+    let ttfts = history
+        .iter()
+        .map(|round| round.ttft_ms)
+        .collect::<Vec<_>>();
+    let durations = history
+        .iter()
+        .map(|round| round.duration_ms)
+        .collect::<Vec<_>>();
+    TimingStats {
+        provider: label.to_string(),
+        last: history.last().copied(),
+        rounds: history.len(),
+        avg_ttft_ms: average(&ttfts),
+        median_ttft_ms: median(&ttfts),
+        avg_duration_ms: average(&durations),
+        median_duration_ms: median(&durations),
+    }
+}
+
+pub fn all() -> Vec<TimingStats> {
+    crate::AI_SERVICES
+        .iter()
+        .map(|(label, _)| stats(label))
+        .collect()
+}
+
+fn average(values: &[u64]) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<u64>() / values.len() as u64)
+}
+
+// An even count averages the two middle values
+fn median(values: &[u64]) -> Option<u64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let middle = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((sorted[middle - 1] + sorted[middle]) / 2),
+        _ => Some(sorted[middle]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_of_empty_is_none() {
+        assert_eq!(median(&[]), None);
+    }
+
+    #[test]
+    fn median_of_odd_count_is_middle_value() {
+        assert_eq!(median(&[300, 100, 200]), Some(200));
+    }
+
+    #[test]
+    fn median_of_even_count_averages_middle_values() {
+        assert_eq!(median(&[400, 100, 300, 200]), Some(250));
+        assert_eq!(median(&[100, 200]), Some(150));
+    }
+}
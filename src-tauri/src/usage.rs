@@ -0,0 +1,321 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use sysinfo::{Pid, System};
+
+#[cfg(target_os = "macos")]
+use libproc::processes;
+#[cfg(target_os = "macos")]
+use std::path::Path;
+
+#[cfg(windows)]
+use crate::{profile, AI_SERVICES};
+
+// WebKitGTK starts every web content process from the app itself and WKWebView from launchd, with
+// nothing on the process naming its data store, and macOS shares one network process across stores.
+// Matching by data directory there only ever found storage and network processes, never renderers.
+#[cfg(not(windows))]
+const BREAKDOWN_UNAVAILABLE: &str =
+    "Per-provider usage isn't available on this platform: web content processes can't be tied to a provider";
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Usage {
+    pub memory_mb: f64,
+    pub cpu_percent: f32,
+    pub process_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderUsage {
+    pub provider: String,
+    #[serde(flatten)]
+    pub usage: Usage,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceUsage {
+    pub total: Usage,
+    /// Empty where the platform gives no way to tie web processes to a provider
+    pub providers: Vec<ProviderUsage>,
+    /// App process plus web processes that could not be tied to a provider
+    pub other: Usage,
+    /// Why `providers` is empty
+    pub breakdown_unavailable: Option<&'static str>,
+}
+
+// Kept between calls so sysinfo can compute CPU usage since the previous refresh
+static SYSTEM: Mutex<Option<System>> = Mutex::new(None);
+
+fn with_system<T>(f: impl FnOnce(&System) -> T) -> T {
+    let mut guard = SYSTEM.lock().unwrap();
+    let system = guard.get_or_insert_with(System::new_all);
+    system.refresh_processes();
+    f(system)
+}
+
+pub fn memory_usage() -> Result<f64, String> {
+    with_system(|system| {
+        let pid = Pid::from_u32(std::process::id());
+        let pids = related_pids(system, pid)?;
+        Ok(sum_usage(system, pids.iter().copied()).memory_mb)
+    })
+}
+
+#[cfg(windows)]
+pub fn resource_usage(app: &tauri::AppHandle) -> Result<ResourceUsage, String> {
+    let active_profile = profile::active_profile();
+    let provider_paths = AI_SERVICES
+        .iter()
+        .filter_map(|(label, _)| {
+            profile::data_directory(app, &active_profile, label)
+                .ok()
+                .map(|path| (*label, path))
+        })
+        .collect::<Vec<_>>();
+
+    with_system(|system| {
+        let pid = Pid::from_u32(std::process::id());
+        let mut remaining = related_pids(system, pid)?;
+        let total = sum_usage(system, remaining.iter().copied());
+
+        let providers = provider_paths
+            .iter()
+            .map(|(label, path)| {
+                let pids = provider_pids(system, &remaining, path);
+                remaining.retain(|pid| !pids.contains(pid));
+                ProviderUsage {
+                    provider: label.to_string(),
+                    usage: sum_usage(system, pids),
+                }
+            })
+            .collect();
+
+        Ok(ResourceUsage {
+            total,
+            providers,
+            other: sum_usage(system, remaining),
+            breakdown_unavailable: None,
+        })
+    })
+}
+
+#[cfg(not(windows))]
+pub fn resource_usage(_app: &tauri::AppHandle) -> Result<ResourceUsage, String> {
+    with_system(|system| {
+        let pid = Pid::from_u32(std::process::id());
+        let pids = related_pids(system, pid)?;
+        let total = sum_usage(system, pids.iter().copied());
+        Ok(ResourceUsage {
+            other: total.clone(),
+            total,
+            providers: Vec::new(),
+            breakdown_unavailable: Some(BREAKDOWN_UNAVAILABLE),
+        })
+    })
+}
+
+fn related_pids(system: &System, root_pid: Pid) -> Result<HashSet<Pid>, String> {
+    let process = system
+        .process(root_pid)
+        .ok_or_else(|| "Process not found".to_string())?;
+
+    #[cfg(target_os = "macos")]
+    let mut pids = {
+        let user_id = process.user_id().cloned();
+        macos_related_pids(system, root_pid, process.start_time(), user_id)
+    };
+
+    #[cfg(not(target_os = "macos"))]
+    let mut pids = system
+        .processes()
+        .values()
+        .filter(|proc| is_descendant(proc, root_pid, system.processes()))
+        .map(|proc| proc.pid())
+        .collect::<HashSet<_>>();
+
+    pids.insert(process.pid());
+    Ok(pids)
+}
+
+// Every data directory runs its own WebView2 browser process, started with --user-data-dir;
+// its renderer, GPU and utility processes are children of that process
+#[cfg(windows)]
+fn provider_pids(
+    system: &System,
+    candidates: &HashSet<Pid>,
+    path: &std::path::Path,
+) -> HashSet<Pid> {
+    let path_str = path.to_string_lossy();
+    let roots = candidates
+        .iter()
+        .copied()
+        .filter(|pid| {
+            system
+                .process(*pid)
+                .is_some_and(|proc| proc.cmd().iter().any(|arg| arg.contains(path_str.as_ref())))
+        })
+        .collect::<Vec<_>>();
+    let parents = candidates
+        .iter()
+        .map(|pid| (*pid, system.process(*pid).and_then(|proc| proc.parent())))
+        .collect();
+    with_descendants(&roots, &parents)
+}
+
+/// The pids in `parents` that are one of `roots` or descend from one
+#[cfg(any(windows, test))]
+fn with_descendants(roots: &[Pid], parents: &HashMap<Pid, Option<Pid>>) -> HashSet<Pid> {
+    parents
+        .keys()
+        .copied()
+        .filter(|pid| {
+            let mut current = Some(*pid);
+            // Bounded in case of a pid reused into a cycle between snapshots
+            for _ in 0..=parents.len() {
+                match current {
+                    Some(pid) if roots.contains(&pid) => return true,
+                    Some(pid) => current = parents.get(&pid).copied().flatten(),
+                    None => return false,
+                }
+            }
+            false
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn macos_webkit_data_store_path() -> Option<std::path::PathBuf> {
+    let home = std::env::var("HOME").ok()?;
+    let bundle_id = "com.seno.viewer";
+    let path = Path::new(&home)
+        .join("Library")
+        .join("WebKit")
+        .join(bundle_id);
+    path.exists().then_some(path)
+}
+
+#[cfg(target_os = "macos")]
+fn macos_related_pids(
+    system: &System,
+    root_pid: Pid,
+    start_time: u64,
+    user_id: Option<sysinfo::Uid>,
+) -> HashSet<Pid> {
+    let mut related_pids = HashSet::new();
+
+    for proc in system.processes().values() {
+        if is_descendant(proc, root_pid, system.processes()) {
+            related_pids.insert(proc.pid());
+        }
+        if proc.name().starts_with("seno ") {
+            related_pids.insert(proc.pid());
+        }
+    }
+
+    if let Some(data_store_path) = macos_webkit_data_store_path() {
+        if let Ok(pids) = processes::pids_by_path(&data_store_path, false, false) {
+            for web_pid in pids {
+                related_pids.insert(Pid::from_u32(web_pid));
+            }
+        }
+    }
+
+    for proc in system.processes().values() {
+        if proc.name() != "com.apple.WebKit.WebContent" {
+            continue;
+        }
+        if proc.start_time() < start_time {
+            continue;
+        }
+        if let Some(user_id) = user_id.as_ref() {
+            if proc.user_id() != Some(user_id) {
+                continue;
+            }
+        }
+        related_pids.insert(proc.pid());
+    }
+
+    related_pids
+}
+
+fn sum_usage(system: &System, pids: impl IntoIterator<Item = Pid>) -> Usage {
+    let mut usage = Usage::default();
+    let mut memory = 0u64;
+    for proc in pids.into_iter().filter_map(|pid| system.process(pid)) {
+        memory += proc.memory();
+        usage.cpu_percent += proc.cpu_usage();
+        usage.process_count += 1;
+    }
+    usage.memory_mb = memory as f64 / (1024.0 * 1024.0);
+    usage
+}
+
+fn is_descendant(
+    process: &sysinfo::Process,
+    root_pid: Pid,
+    processes: &HashMap<Pid, sysinfo::Process>,
+) -> bool {
+    let mut current = process.parent();
+    while let Some(pid) = current {
+        if pid == root_pid {
+            return true;
+        }
+        current = processes.get(&pid).and_then(|proc| proc.parent());
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pid(pid: u32) -> Pid {
+        Pid::from_u32(pid)
+    }
+
+    // app(1) -> browser(10) -> renderer(11) -> utility(12), browser(20) -> renderer(21), network(30)
+    fn tree() -> HashMap<Pid, Option<Pid>> {
+        [
+            (1, None),
+            (10, Some(1)),
+            (11, Some(10)),
+            (12, Some(11)),
+            (20, Some(1)),
+            (21, Some(20)),
+            (30, Some(1)),
+        ]
+        .into_iter()
+        .map(|(child, parent)| (pid(child), parent.map(pid)))
+        .collect()
+    }
+
+    #[test]
+    fn descendants_of_a_browser_process_belong_to_its_provider() {
+        let pids = with_descendants(&[pid(10)], &tree());
+        assert_eq!(pids, HashSet::from([pid(10), pid(11), pid(12)]));
+    }
+
+    #[test]
+    fn sibling_trees_are_kept_apart() {
+        let tree = tree();
+        let first = with_descendants(&[pid(10)], &tree);
+        let second = with_descendants(&[pid(20)], &tree);
+        assert!(first.is_disjoint(&second));
+        assert_eq!(second, HashSet::from([pid(20), pid(21)]));
+    }
+
+    #[test]
+    fn unmatched_processes_stay_out() {
+        assert!(with_descendants(&[], &tree()).is_empty());
+        assert!(!with_descendants(&[pid(10), pid(20)], &tree()).contains(&pid(30)));
+    }
+
+    #[test]
+    fn parent_cycles_end() {
+        let cycle = HashMap::from([(pid(1), Some(pid(2))), (pid(2), Some(pid(1)))]);
+        assert!(with_descendants(&[pid(3)], &cycle).is_empty());
+    }
+}
@@ -1,11 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import {
-  isPermissionGranted,
-  requestPermission,
-  sendNotification,
-} from "@tauri-apps/plugin-notification";
-import { platform } from "@tauri-apps/plugin-os";
 import { relaunch } from "@tauri-apps/plugin-process";
 import { check, type Update } from "@tauri-apps/plugin-updater";
 
@@ -23,6 +17,48 @@ interface DisplaySettings {
   elements: TitlebarElement[];
 }
 
+interface Usage {
+  memoryMb: number;
+  cpuPercent: number;
+  processCount: number;
+}
+
+interface QueuedPrompt {
+  id: number;
+  text: string;
+}
+
+interface RoundTiming {
+  sentAt: number;
+  ttftMs: number;
+  durationMs: number;
+}
+
+interface TimingStats {
+  provider: string;
+  last: RoundTiming | null;
+  rounds: number;
+  avgTtftMs: number | null;
+  medianTtftMs: number | null;
+  avgDurationMs: number | null;
+  medianDurationMs: number | null;
+}
+
+interface BatchProgress {
+  running: boolean;
+  completed: number;
+  total: number;
+  current: string | null;
+  resultsPath: string;
+}
+
+interface ResourceUsage {
+  total: Usage;
+  providers: (Usage & { provider: string })[];
+  other: Usage;
+  breakdownUnavailable: string | null;
+}
+
 declare global {
   interface Window {
     __seno_toast_enabled?: boolean;
@@ -90,56 +126,6 @@ if (view === "titlebar") {
     gemini: document.getElementById("status-gemini"),
   };
 
-  // Track streaming status for all providers
-  const providerStates: Record<string, string> = {
-    claude: "unknown",
-    chatgpt: "unknown",
-    gemini: "unknown",
-  };
-
-  // Track if any provider was streaming (to detect completion)
-  let wasAnyStreaming = false;
-
-  function checkAllIdleAndNotify(): void {
-    const allIdle = Object.values(providerStates).every(
-      (state) => state === "idle"
-    );
-
-    if (allIdle && wasAnyStreaming) {
-      wasAnyStreaming = false;
-      sendCompletionNotification();
-    }
-  }
-
-  async function sendCompletionNotification(): Promise<void> {
-    try {
-      let hasPermission = await isPermissionGranted();
-      if (!hasPermission) {
-        hasPermission = (await requestPermission()) === "granted";
-      }
-      if (!hasPermission) return;
-
-      // Get platform-specific notification sound
-      const currentPlatform = platform();
-      let sound: string | undefined;
-
-      if (currentPlatform === "macos") {
-        sound = "Ping";  // macOS system sound
-      } else if (currentPlatform === "linux") {
-        sound = "message-new-instant";  // XDG theme sound
-      }
-      // Windows: undefined (uses OS default notification sound)
-
-      sendNotification({
-        title: "Seno",
-        body: "All AI responses completed",
-        sound,
-      });
-    } catch (e) {
-      console.warn("Notification failed:", e);
-    }
-  }
-
   async function checkForUpdates(): Promise<void> {
     try {
       const update = await check();
@@ -180,20 +166,17 @@ if (view === "titlebar") {
     const text = statusTexts[provider];
     if (!item || !text) return;
 
-    const prevState = providerStates[provider];
-    providerStates[provider] = state;
     item.dataset.state = state;
 
     switch (state) {
       case "streaming":
-        wasAnyStreaming = true;
         text.textContent = "Streaming";
         break;
       case "idle":
         text.textContent = "Idle";
-        if (prevState === "streaming") {
-          checkAllIdleAndNotify();
-        }
+        break;
+      case "suspended":
+        text.textContent = "Suspended";
         break;
       default:
         text.textContent = "Unknown";
@@ -211,9 +194,17 @@ if (view === "titlebar") {
 
   async function refreshMemoryUsage(): Promise<void> {
     try {
-      const memoryMb = await invoke<number>("get_memory_usage");
+      const usage = await invoke<ResourceUsage>("get_resource_usage");
       if (memoryIndicator) {
-        memoryIndicator.textContent = `Memory: ${formatMemory(memoryMb)}`;
+        memoryIndicator.textContent = `Memory: ${formatMemory(usage.total.memoryMb)}`;
+        memoryIndicator.title = usage.breakdownUnavailable
+          ? `total: ${formatMemory(usage.total.memoryMb)} / CPU ${usage.total.cpuPercent.toFixed(1)}%\n${usage.breakdownUnavailable}`
+          : [
+              ...usage.providers.map(
+                (p) => `${p.provider}: ${formatMemory(p.memoryMb)} / CPU ${p.cpuPercent.toFixed(1)}%`,
+              ),
+              `other: ${formatMemory(usage.other.memoryMb)} / CPU ${usage.other.cpuPercent.toFixed(1)}%`,
+            ].join("\n");
       }
     } catch (e) {
       console.warn("Failed to get memory usage:", e);
@@ -257,6 +248,99 @@ if (view === "titlebar") {
     console.warn("Failed to listen provider status:", err);
   });
 
+  function handleSendQueue(event: { payload: { provider: string; prompts: QueuedPrompt[] } }): void {
+    const badge = document.getElementById(`queue-${event.payload.provider}`);
+    if (!badge) return;
+    const prompts = event.payload.prompts;
+    badge.style.display = prompts.length ? "" : "none";
+    badge.textContent = `+${prompts.length}`;
+    badge.title = [
+      ...prompts.map((p, i) => `${i + 1}. ${p.text.split("\n")[0].slice(0, 60)}`),
+      "Click to cancel queued prompts",
+    ].join("\n");
+  }
+
+  document.querySelectorAll<HTMLElement>(".service-status-queue").forEach((badge) => {
+    const provider = badge.id.replace("queue-", "");
+    badge.addEventListener("click", () => {
+      invoke("cancel_queued_prompt", { provider }).catch((err) => {
+        console.warn("Failed to cancel queued prompts:", err);
+      });
+    });
+  });
+
+  listen<{ provider: string; prompts: QueuedPrompt[] }>("send-queue-changed", handleSendQueue).catch((err) => {
+    console.warn("Failed to listen send queue:", err);
+  });
+
+  function setModel(provider: string, model: string): void {
+    const el = document.getElementById(`model-${provider}`);
+    if (!el) return;
+    el.textContent = model;
+    el.title = model;
+  }
+
+  invoke<Record<string, string>>("get_current_models")
+    .then((models) => {
+      Object.entries(models).forEach(([provider, model]) => setModel(provider, model));
+    })
+    .catch((err) => {
+      console.warn("Failed to get current models:", err);
+    });
+
+  listen<{ provider: string; model: string }>("provider-model", (event) => {
+    setModel(event.payload.provider, event.payload.model);
+  }).catch((err) => {
+    console.warn("Failed to listen provider model:", err);
+  });
+
+  function formatSeconds(ms: number | null): string {
+    return ms === null ? "--" : `${(ms / 1000).toFixed(1)}s`;
+  }
+
+  function setTiming(stats: TimingStats): void {
+    const el = document.getElementById(`timing-${stats.provider}`);
+    if (!el || !stats.last) return;
+    el.textContent = formatSeconds(stats.last.durationMs);
+    el.title = [
+      `Last: first token ${formatSeconds(stats.last.ttftMs)}, done ${formatSeconds(stats.last.durationMs)}`,
+      `Average of ${stats.rounds}: first token ${formatSeconds(stats.avgTtftMs)}, done ${formatSeconds(stats.avgDurationMs)}`,
+      `Median: first token ${formatSeconds(stats.medianTtftMs)}, done ${formatSeconds(stats.medianDurationMs)}`,
+    ].join("\n");
+  }
+
+  invoke<TimingStats[]>("get_timing_stats")
+    .then((stats) => stats.forEach(setTiming))
+    .catch((err) => {
+      console.warn("Failed to get timing stats:", err);
+    });
+
+  listen<TimingStats>("provider-timing", (event) => {
+    setTiming(event.payload);
+  }).catch((err) => {
+    console.warn("Failed to listen provider timing:", err);
+  });
+
+  const batchProgress = document.getElementById("batch-progress") as HTMLButtonElement | null;
+
+  function handleBatchProgress(event: { payload: BatchProgress }): void {
+    if (!batchProgress) return;
+    const { running, completed, total, current } = event.payload;
+    batchProgress.style.display = running ? "" : "none";
+    batchProgress.textContent = `Batch ${completed + 1}/${total}`;
+    batchProgress.title = [current ?? "", "Click to cancel the batch"].join("\n");
+  }
+
+  batchProgress?.addEventListener("click", () => {
+    invoke("cancel_batch").catch((err) => {
+      console.warn("Failed to cancel batch:", err);
+    });
+  });
+
+  listen<BatchProgress>("batch-progress", handleBatchProgress).catch((err) => {
+    console.warn("Failed to listen batch progress:", err);
+  });
+
   let toastTimer: number | null = null;
 
   function handleProviderToast(event: { payload: { provider: string; message: string } }): void {
@@ -313,14 +397,15 @@ if (view === "titlebar") {
     });
   }
 
-  async function sendToAll(): Promise<void> {
+  // "edit_last_prompt" replaces the previous prompt in each thread instead of sending a new one
+  async function sendToAll(command: "send_to_all" | "edit_last_prompt" = "send_to_all"): Promise<void> {
     const text = input.value.trim();
     if (!text) return;
 
     sendBtn.disabled = true;
 
     try {
-      await invoke("send_to_all", { text });
+      await invoke(command, { text });
       input.value = "";
       resizeTextarea();
     } catch (err) {
@@ -349,12 +434,12 @@ if (view === "titlebar") {
     updateInputBarHeight(nextHeight);
   }
 
-  sendBtn.addEventListener("click", sendToAll);
+  sendBtn.addEventListener("click", () => sendToAll());
 
   input.addEventListener("keydown", (e) => {
     if (e.key === "Enter" && e.metaKey) {
       e.preventDefault();
-      sendToAll();
+      sendToAll(e.shiftKey ? "edit_last_prompt" : "send_to_all");
     }
   });
 
@@ -375,4 +460,20 @@ if (view === "titlebar") {
 
   focusInput();
   window.addEventListener("focus", () => setTimeout(focusInput, 50));
+
+  // Prompt text handed over by a launch of `seno <prompt>` or `seno <file>`
+  async function applyPendingInput(): Promise<void> {
+    const text = await invoke<string | null>("take_pending_input");
+    if (!text) return;
+    input.value = text;
+    resizeTextarea();
+    await focusInput();
+  }
+
+  applyPendingInput().catch((err) => console.warn("Failed to take pending input:", err));
+  listen("pending-input", () => {
+    applyPendingInput().catch((err) => console.warn("Failed to take pending input:", err));
+  }).catch((err) => {
+    console.warn("Failed to listen pending input:", err);
+  });
 }
@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 
 interface TitlebarElement {
   id: string;
@@ -9,6 +10,63 @@ interface DisplaySettings {
   elements: TitlebarElement[];
 }
 
+interface MemoryBudget {
+  enabled: boolean;
+  providerLimitMb: number | null;
+  totalLimitMb: number | null;
+  action: "reload" | "suspend";
+}
+
+interface HttpApi {
+  enabled: boolean;
+  port: number;
+  token: string;
+}
+
+interface Inbox {
+  enabled: boolean;
+  dir: string;
+}
+
+interface QuietHours {
+  enabled: boolean;
+  start: string;
+  end: string;
+}
+
+interface Notifications {
+  enabled: boolean;
+  mode: "eachProvider" | "allDone";
+  includeAnswer: boolean;
+  quietHours: QuietHours;
+  minDurationSecs: number;
+  muted: string[];
+}
+
+interface AppSettings {
+  memoryBudget: MemoryBudget;
+  restoreLastConversation: boolean;
+  httpApi: HttpApi;
+  inbox: Inbox;
+  notifications: Notifications;
+}
+
+interface Profile {
+  id: string;
+  name: string;
+}
+
+interface Session {
+  id: string;
+  name: string;
+  pinned: boolean;
+}
+
+interface ProfileSettings {
+  active: string;
+  profiles: Profile[];
+}
+
 const ELEMENT_LABELS: Record<string, string> = {
   memory: "Memory",
   serviceStatus: "Status",
@@ -154,4 +212,327 @@ function handleMouseUp(): void {
   draggedIndex = -1;
 }
 
+// Profiles
+const profileListEl = document.getElementById("profile-list")!;
+const profileAddForm = document.getElementById("profile-add") as HTMLFormElement;
+const profileNameInput = document.getElementById("profile-name") as HTMLInputElement;
+
+function renderProfiles(profiles: ProfileSettings): void {
+  profileListEl.innerHTML = "";
+  profiles.profiles.forEach((profile) => {
+    const isActive = profile.id === profiles.active;
+    const item = document.createElement("div");
+    item.className = isActive ? "settings-item profile-item active" : "settings-item profile-item";
+
+    const name = document.createElement("input");
+    name.type = "text";
+    name.value = profile.name;
+    name.addEventListener("change", () => {
+      invoke("rename_profile", { id: profile.id, name: name.value }).catch((e) => {
+        console.warn("[settings] Failed to rename profile:", e);
+        name.value = profile.name;
+      });
+    });
+
+    const use = document.createElement("button");
+    use.className = "profile-action";
+    use.textContent = isActive ? "Active" : "Use";
+    use.disabled = isActive;
+    use.addEventListener("click", () => {
+      invoke("switch_profile", { id: profile.id }).catch((e) => {
+        console.warn("[settings] Failed to switch profile:", e);
+      });
+    });
+
+    const remove = document.createElement("button");
+    remove.className = "profile-action";
+    remove.textContent = "Delete";
+    remove.disabled = isActive || profile.id === "default";
+    remove.addEventListener("click", () => {
+      invoke("delete_profile", { id: profile.id }).catch((e) => {
+        console.warn("[settings] Failed to delete profile:", e);
+      });
+    });
+
+    item.append(name, use, remove);
+    profileListEl.appendChild(item);
+  });
+}
+
+async function loadProfiles(): Promise<void> {
+  try {
+    renderProfiles(await invoke<ProfileSettings>("get_profiles"));
+  } catch (e) {
+    console.warn("[settings] Failed to load profiles:", e);
+  }
+}
+
+profileAddForm.addEventListener("submit", (e) => {
+  e.preventDefault();
+  const name = profileNameInput.value.trim();
+  if (!name) return;
+  invoke("create_profile", { name })
+    .then(() => {
+      profileNameInput.value = "";
+    })
+    .catch((err) => {
+      console.warn("[settings] Failed to create profile:", err);
+    });
+});
+
+listen<ProfileSettings>("profiles-changed", (event) => renderProfiles(event.payload)).catch((err) => {
+  console.warn("[settings] Failed to listen profile changes:", err);
+});
+
+// Sessions
+const sessionListEl = document.getElementById("session-list")!;
+
+function renderSessions(sessions: Session[]): void {
+  sessionListEl.innerHTML = "";
+  sessions.forEach((session) => {
+    const item = document.createElement("div");
+    item.className = session.pinned ? "settings-item profile-item active" : "settings-item profile-item";
+
+    const name = document.createElement("input");
+    name.type = "text";
+    name.value = session.name;
+    name.addEventListener("change", () => {
+      invoke("rename_session", { id: session.id, name: name.value }).catch((e) => {
+        console.warn("[settings] Failed to rename session:", e);
+        name.value = session.name;
+      });
+    });
+
+    const pin = document.createElement("button");
+    pin.className = "profile-action";
+    pin.textContent = session.pinned ? "Unpin" : "Pin";
+    pin.addEventListener("click", () => {
+      invoke("pin_session", { id: session.id, pinned: !session.pinned }).catch((e) => {
+        console.warn("[settings] Failed to pin session:", e);
+      });
+    });
+
+    const open = document.createElement("button");
+    open.className = "profile-action";
+    open.textContent = "Open";
+    open.addEventListener("click", () => {
+      invoke("open_session", { id: session.id }).catch((e) => {
+        console.warn("[settings] Failed to open session:", e);
+      });
+    });
+
+    const remove = document.createElement("button");
+    remove.className = "profile-action";
+    remove.textContent = "Delete";
+    remove.addEventListener("click", () => {
+      invoke("delete_session", { id: session.id }).catch((e) => {
+        console.warn("[settings] Failed to delete session:", e);
+      });
+    });
+
+    item.append(name, pin, open, remove);
+    sessionListEl.appendChild(item);
+  });
+}
+
+async function loadSessions(): Promise<void> {
+  try {
+    renderSessions(await invoke<Session[]>("list_sessions"));
+  } catch (e) {
+    console.warn("[settings] Failed to load sessions:", e);
+  }
+}
+
+listen<Session[]>("sessions-changed", (event) => renderSessions(event.payload)).catch((err) => {
+  console.warn("[settings] Failed to listen session changes:", err);
+});
+
+// App settings
+let appSettings: AppSettings | null = null;
+
+const restoreLast = document.getElementById("restore-last") as HTMLInputElement;
+const budgetEnabled = document.getElementById("budget-enabled") as HTMLInputElement;
+const budgetProvider = document.getElementById("budget-provider") as HTMLInputElement;
+const budgetTotal = document.getElementById("budget-total") as HTMLInputElement;
+const budgetAction = document.getElementById("budget-action") as HTMLSelectElement;
+const notifyEnabled = document.getElementById("notify-enabled") as HTMLInputElement;
+const notifyMode = document.getElementById("notify-mode") as HTMLSelectElement;
+const notifyAnswer = document.getElementById("notify-answer") as HTMLInputElement;
+const notifyMinDuration = document.getElementById("notify-min-duration") as HTMLInputElement;
+const quietEnabled = document.getElementById("quiet-enabled") as HTMLInputElement;
+const quietStart = document.getElementById("quiet-start") as HTMLInputElement;
+const quietEnd = document.getElementById("quiet-end") as HTMLInputElement;
+const soundToggles = Array.from(document.querySelectorAll<HTMLInputElement>('[id^="sound-"]'));
+const apiEnabled = document.getElementById("api-enabled") as HTMLInputElement;
+const apiPort = document.getElementById("api-port") as HTMLInputElement;
+const apiToken = document.getElementById("api-token") as HTMLInputElement;
+const apiTokenReset = document.getElementById("api-token-reset") as HTMLButtonElement;
+const inboxEnabled = document.getElementById("inbox-enabled") as HTMLInputElement;
+const inboxDir = document.getElementById("inbox-dir") as HTMLInputElement;
+const inboxChoose = document.getElementById("inbox-choose") as HTMLButtonElement;
+
+function parseLimit(value: string): number | null {
+  const parsed = parseInt(value, 10);
+  return Number.isFinite(parsed) && parsed > 0 ? parsed : null;
+}
+
+function renderAppSettings(): void {
+  if (!appSettings) return;
+  restoreLast.checked = appSettings.restoreLastConversation;
+  const budget = appSettings.memoryBudget;
+  budgetEnabled.checked = budget.enabled;
+  budgetProvider.value = budget.providerLimitMb ? String(budget.providerLimitMb) : "";
+  budgetTotal.value = budget.totalLimitMb ? String(budget.totalLimitMb) : "";
+  budgetAction.value = budget.action;
+  notifyEnabled.checked = appSettings.notifications.enabled;
+  notifyMode.value = appSettings.notifications.mode;
+  notifyAnswer.checked = appSettings.notifications.includeAnswer;
+  notifyMinDuration.value = appSettings.notifications.minDurationSecs
+    ? String(appSettings.notifications.minDurationSecs)
+    : "";
+  quietEnabled.checked = appSettings.notifications.quietHours.enabled;
+  quietStart.value = appSettings.notifications.quietHours.start;
+  quietEnd.value = appSettings.notifications.quietHours.end;
+  soundToggles.forEach((toggle) => {
+    toggle.checked = !appSettings!.notifications.muted.includes(toggle.id.replace("sound-", ""));
+  });
+  apiEnabled.checked = appSettings.httpApi.enabled;
+  apiPort.value = String(appSettings.httpApi.port);
+  apiToken.value = appSettings.httpApi.token;
+  inboxEnabled.checked = appSettings.inbox.enabled;
+  inboxDir.value = appSettings.inbox.dir;
+  inboxDir.title = appSettings.inbox.dir;
+}
+
+async function loadAppSettings(): Promise<void> {
+  try {
+    appSettings = await invoke<AppSettings>("get_app_settings");
+    renderAppSettings();
+  } catch (e) {
+    console.warn("[settings] Failed to load app settings:", e);
+  }
+}
+
+async function saveAppSettings(): Promise<void> {
+  if (!appSettings) return;
+  try {
+    await invoke("set_app_settings", { settings: appSettings });
+    // The backend fills in a fresh API token when it was cleared
+    if (!appSettings.httpApi.token) await loadAppSettings();
+  } catch (e) {
+    console.warn("[settings] Failed to save app settings:", e);
+  }
+}
+
+function handleBudgetChange(): void {
+  if (!appSettings) return;
+  appSettings.memoryBudget = {
+    enabled: budgetEnabled.checked,
+    providerLimitMb: parseLimit(budgetProvider.value),
+    totalLimitMb: parseLimit(budgetTotal.value),
+    action: budgetAction.value as MemoryBudget["action"],
+  };
+  saveAppSettings();
+}
+
+restoreLast.addEventListener("change", () => {
+  if (!appSettings) return;
+  appSettings.restoreLastConversation = restoreLast.checked;
+  saveAppSettings();
+});
+
+[budgetEnabled, budgetProvider, budgetTotal, budgetAction].forEach((el) => {
+  el.addEventListener("change", handleBudgetChange);
+});
+
+function handleNotifyChange(): void {
+  if (!appSettings) return;
+  appSettings.notifications = {
+    ...appSettings.notifications,
+    enabled: notifyEnabled.checked,
+    mode: notifyMode.value as Notifications["mode"],
+    includeAnswer: notifyAnswer.checked,
+    minDurationSecs: parseLimit(notifyMinDuration.value) ?? 0,
+    quietHours: {
+      enabled: quietEnabled.checked,
+      start: quietStart.value || appSettings.notifications.quietHours.start,
+      end: quietEnd.value || appSettings.notifications.quietHours.end,
+    },
+    muted: soundToggles
+      .filter((toggle) => !toggle.checked)
+      .map((toggle) => toggle.id.replace("sound-", "")),
+  };
+  saveAppSettings();
+}
+
+[
+  notifyEnabled,
+  notifyMode,
+  notifyAnswer,
+  notifyMinDuration,
+  quietEnabled,
+  quietStart,
+  quietEnd,
+  ...soundToggles,
+].forEach((el) => {
+  el.addEventListener("change", handleNotifyChange);
+});
+
+function handleApiChange(): void {
+  if (!appSettings) return;
+  const port = parseInt(apiPort.value, 10);
+  appSettings.httpApi = {
+    ...appSettings.httpApi,
+    enabled: apiEnabled.checked,
+    port: Number.isFinite(port) && port > 0 && port < 65536 ? port : appSettings.httpApi.port,
+  };
+  saveAppSettings();
+}
+
+[apiEnabled, apiPort].forEach((el) => {
+  el.addEventListener("change", handleApiChange);
+});
+
+apiTokenReset.addEventListener("click", () => {
+  if (!appSettings) return;
+  appSettings.httpApi = { ...appSettings.httpApi, token: "" };
+  saveAppSettings();
+});
+
+inboxEnabled.addEventListener("change", () => {
+  if (!appSettings) return;
+  appSettings.inbox = { ...appSettings.inbox, enabled: inboxEnabled.checked };
+  saveAppSettings();
+});
+
+inboxChoose.addEventListener("click", async () => {
+  if (!appSettings) return;
+  try {
+    const dir = await invoke<string | null>("choose_inbox_folder");
+    if (!dir) return;
+    appSettings.inbox = { ...appSettings.inbox, dir };
+    renderAppSettings();
+    saveAppSettings();
+  } catch (e) {
+    console.warn("[settings] Failed to choose inbox folder:", e);
+  }
+});
+
+// The per-provider limit needs a per-provider breakdown, which not every platform has
+async function checkUsageBreakdown(): Promise<void> {
+  try {
+    const usage = await invoke<{ breakdownUnavailable: string | null }>("get_resource_usage");
+    if (usage.breakdownUnavailable) {
+      budgetProvider.disabled = true;
+      budgetProvider.title = usage.breakdownUnavailable;
+    }
+  } catch (e) {
+    console.warn("[settings] Failed to get resource usage:", e);
+  }
+}
+
 loadSettings();
+loadProfiles();
+loadSessions();
+loadAppSettings();
+checkUsageBreakdown();